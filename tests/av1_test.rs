@@ -0,0 +1,34 @@
+// Unit tests for the AV1 RTP aggregation-unit parser.
+use websink::websink::av1::parse_aggregation_units;
+
+#[test]
+fn test_single_obu_w1() {
+    // W=1 (bits `W W` = 01 at positions 4-5): one OBU element, no length prefix.
+    // Header byte: Z=0 Y=0 W=01 N=0 -> 0b0001_0000 = 0x10.
+    let payload = [0x10, 0xaa, 0xbb, 0xcc];
+    let obus = parse_aggregation_units(&payload).expect("should parse single-OBU packet");
+    assert_eq!(obus.len(), 1);
+    assert_eq!(obus[0], &[0xaa, 0xbb, 0xcc]);
+}
+
+#[test]
+fn test_two_obus_w0_length_prefixed() {
+    // W=0: every element is LEB128 length-prefixed.
+    let payload = [0x00, 0x02, 0xaa, 0xbb, 0x01, 0xcc];
+    let obus = parse_aggregation_units(&payload).expect("should parse length-prefixed payload");
+    assert_eq!(obus.len(), 2);
+    assert_eq!(obus[0], &[0xaa, 0xbb]);
+    assert_eq!(obus[1], &[0xcc]);
+}
+
+#[test]
+fn test_truncated_payload_rejected() {
+    // A declared length that runs past the buffer must be rejected, not panic.
+    let payload = [0x00, 0x05, 0xaa];
+    assert!(parse_aggregation_units(&payload).is_none());
+}
+
+#[test]
+fn test_empty_payload_rejected() {
+    assert!(parse_aggregation_units(&[]).is_none());
+}
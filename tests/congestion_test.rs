@@ -0,0 +1,36 @@
+// Unit tests for the GCC bandwidth estimator.
+use websink::websink::congestion::{BandwidthEstimator, PacketFeedback};
+
+const MIN: u32 = 100_000;
+const MAX: u32 = 8_000_000;
+
+#[test]
+fn test_stable_link_holds_at_max() {
+    // Arrival spacing matches send spacing (zero delay gradient) and no loss, so
+    // the estimator should stay pinned at the ceiling.
+    let mut estimator = BandwidthEstimator::new(MIN, MAX);
+    let packets: Vec<PacketFeedback> =
+        (0..40).map(|i| PacketFeedback { send_time_us: i * 10_000, arrival_time_us: i * 10_000 }).collect();
+    let target = estimator.process_feedback(&packets, 0);
+    assert_eq!(target, MAX, "a stable link should hold the maximum bitrate");
+}
+
+#[test]
+fn test_growing_delay_backs_off() {
+    // Arrival spacing grows faster than send spacing: a sustained positive delay
+    // gradient that must trip overuse and pull the target below the ceiling.
+    let mut estimator = BandwidthEstimator::new(MIN, MAX);
+    let packets: Vec<PacketFeedback> =
+        (0..40).map(|i| PacketFeedback { send_time_us: i * 10_000, arrival_time_us: i * 25_000 }).collect();
+    let target = estimator.process_feedback(&packets, 0);
+    assert!(target < MAX, "a congested link should back off below the maximum");
+}
+
+#[test]
+fn test_heavy_loss_backs_off() {
+    let mut estimator = BandwidthEstimator::new(MIN, MAX);
+    let packets = [PacketFeedback { send_time_us: 0, arrival_time_us: 0 }];
+    let target = estimator.process_feedback(&packets, 20);
+    assert!(target < MAX, "heavy loss should reduce the target bitrate");
+    assert!(target >= MIN, "the target must never drop below the floor");
+}
@@ -0,0 +1,81 @@
+// AV1 RTP aggregation-unit validation.
+//
+// GStreamer's `rtpav1pay` packs AV1 OBUs into the RTP payload format described by
+// the AOM AV1 RTP spec: a one-byte aggregation header followed by a sequence of
+// length-delimited OBU elements. This module parses that framing so the server
+// can validate well-formed packets and drop malformed ones early rather than
+// carrying them to a downstream discontinuity.
+
+/// Read a LEB128-encoded unsigned value from `data`, returning the value and the
+/// number of bytes consumed. Returns `None` on truncation or overflow.
+fn read_leb128(data: &[u8]) -> Option<(u64, usize)> {
+    let mut value: u64 = 0;
+    for (i, &byte) in data.iter().enumerate().take(8) {
+        value |= ((byte & 0x7f) as u64) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Parse the aggregation header and element sizes of one AV1 RTP payload into the
+/// byte ranges of its constituent OBUs.
+///
+/// The payload layout is: a one-byte aggregation header (whose bits 5-4 are the
+/// `W` field — the number of OBU elements, or 0 meaning "length-prefixed until the
+/// end of the payload") followed by the OBU elements. Each element before the last
+/// (or every element when `W == 0`) is preceded by a LEB128 length.
+///
+/// This does not reassemble or rewrite OBUs — it only validates that the
+/// aggregation framing is well-formed. The caller forwards the original RTP
+/// packet unchanged and uses the `None` case purely as a drop-gate for
+/// malformed payloads; the returned slices are otherwise unused.
+///
+/// Returns the list of OBU slices, or `None` if the payload is unparseable so the
+/// caller can drop the packet.
+pub fn parse_aggregation_units(payload: &[u8]) -> Option<Vec<&[u8]>> {
+    if payload.is_empty() {
+        return None;
+    }
+
+    let header = payload[0];
+    // Aggregation header bits are `Z Y W W N - - -` (MSB first); the OBU-element
+    // count lives in the two `W` bits, not the reserved low bits.
+    let w = ((header >> 4) & 0b0000_0011) as usize; // number of OBU elements
+    let mut pos = 1;
+    let mut obus = Vec::new();
+    let mut index = 0;
+
+    while pos < payload.len() {
+        index += 1;
+        let is_last = w != 0 && index == w;
+
+        // Every element except the final one of a counted (W != 0) payload carries
+        // a LEB128 length prefix.
+        let elem_len = if is_last {
+            payload.len() - pos
+        } else {
+            let (len, consumed) = read_leb128(&payload[pos..])?;
+            pos += consumed;
+            len as usize
+        };
+
+        if pos + elem_len > payload.len() {
+            return None;
+        }
+
+        obus.push(&payload[pos..pos + elem_len]);
+        pos += elem_len;
+
+        if is_last {
+            break;
+        }
+    }
+
+    if obus.is_empty() {
+        None
+    } else {
+        Some(obus)
+    }
+}
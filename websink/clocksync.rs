@@ -0,0 +1,99 @@
+// In-band RFC 6051 rapid-sync timestamping.
+//
+// RFC 7273 signals the reference clock in the SDP, but precise A/V sync also needs
+// the RFC 6051 `ntp-64` header extension carried on the media itself so a receiver
+// can map each packet's RTP timestamp onto that clock without waiting for a sender
+// report. webrtc-rs negotiates the extension (see `build_peer_connection`) but
+// never writes it, so this interceptor stamps every outgoing packet with the
+// current 64-bit NTP time when clock signalling is enabled.
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use webrtc::interceptor::stream_info::StreamInfo;
+use webrtc::interceptor::{
+    Attributes, Error as InterceptorError, Interceptor, InterceptorBuilder, RTCPReader, RTCPWriter, RTPReader, RTPWriter,
+};
+
+use crate::websink::server::NTP64_URI;
+
+/// Seconds between the NTP epoch (1900-01-01) and the Unix epoch (1970-01-01).
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+/// Current time as a 64-bit NTP timestamp: 32-bit seconds since 1900 in the high
+/// word, 32-bit binary fraction of a second in the low word.
+fn ntp_now() -> u64 {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs() + NTP_UNIX_OFFSET;
+    let frac = ((since_epoch.subsec_nanos() as u64) << 32) / 1_000_000_000;
+    ((secs & 0xffff_ffff) << 32) | frac
+}
+
+/// Interceptor builder registered when clock signalling is enabled.
+pub struct NtpStampBuilder;
+
+impl InterceptorBuilder for NtpStampBuilder {
+    fn build(&self, _id: &str) -> Result<Arc<dyn Interceptor + Send + Sync>, InterceptorError> {
+        Ok(Arc::new(NtpStamp))
+    }
+}
+
+struct NtpStamp;
+
+#[async_trait]
+impl Interceptor for NtpStamp {
+    async fn bind_rtcp_reader(&self, reader: Arc<dyn RTCPReader + Send + Sync>) -> Arc<dyn RTCPReader + Send + Sync> {
+        reader
+    }
+
+    async fn bind_rtcp_writer(&self, writer: Arc<dyn RTCPWriter + Send + Sync>) -> Arc<dyn RTCPWriter + Send + Sync> {
+        writer
+    }
+
+    async fn bind_local_stream(
+        &self,
+        info: &StreamInfo,
+        writer: Arc<dyn RTPWriter + Send + Sync>,
+    ) -> Arc<dyn RTPWriter + Send + Sync> {
+        let ntp_id = info.rtp_header_extensions.iter().find(|e| e.uri == NTP64_URI).map(|e| e.id as u8);
+        Arc::new(StampingWriter { next: writer, ntp_id })
+    }
+
+    async fn unbind_local_stream(&self, _info: &StreamInfo) {}
+
+    async fn bind_remote_stream(
+        &self,
+        _info: &StreamInfo,
+        reader: Arc<dyn RTPReader + Send + Sync>,
+    ) -> Arc<dyn RTPReader + Send + Sync> {
+        reader
+    }
+
+    async fn unbind_remote_stream(&self, _info: &StreamInfo) {}
+
+    async fn close(&self) -> Result<(), InterceptorError> {
+        Ok(())
+    }
+}
+
+struct StampingWriter {
+    next: Arc<dyn RTPWriter + Send + Sync>,
+    ntp_id: Option<u8>,
+}
+
+#[async_trait]
+impl RTPWriter for StampingWriter {
+    async fn write(&self, pkt: &webrtc::rtp::packet::Packet, attributes: &Attributes) -> Result<usize, InterceptorError> {
+        if let Some(id) = self.ntp_id {
+            // `set_extension` needs an owned header, so stamp a clone and forward it.
+            let mut stamped = pkt.clone();
+            let ntp = ntp_now().to_be_bytes();
+            if stamped.header.set_extension(id, Bytes::copy_from_slice(&ntp)).is_ok() {
+                return self.next.write(&stamped, attributes).await;
+            }
+        }
+        self.next.write(pkt, attributes).await
+    }
+}
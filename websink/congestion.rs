@@ -0,0 +1,204 @@
+// Google Congestion Control (GCC) delay-based bandwidth estimator.
+//
+// This is the congestion-control subsystem used by the `server` module to turn
+// transport-wide-cc (TWCC) RTCP feedback into a recommended encoder bitrate. The
+// element in `imp.rs` walks its sink pad peer chain to the upstream encoder and
+// applies the estimate to the encoder's `bitrate` (x264enc/x265enc) or
+// `target-bitrate` (vp8enc/vp9enc) property.
+//
+// The delay-based estimator follows the draft-holmer-rmcat-gcc design: outgoing
+// packets are grouped into short send bursts, the inter-group delay variation is
+// accumulated, and a linear regression over a sliding window of the accumulated
+// samples yields an overuse/normal/underuse signal. The regression flavour is
+// preferred over a Kalman filter because it stays stable on low-end devices.
+
+use std::collections::VecDeque;
+
+/// Packets sent within this window are treated as one send burst.
+const BURST_TIME_US: i64 = 5_000;
+/// Number of accumulated-delay samples kept for the regression window.
+const WINDOW_MIN: usize = 20;
+const WINDOW_MAX: usize = 60;
+/// Multiplicative back-off applied to the target on sustained overuse.
+const DECREASE_FACTOR: f64 = 0.85;
+/// Multiplicative increase applied per update while the link is normal (and, in
+/// the loss-based estimate, while loss is low).
+const INCREASE_FACTOR: f64 = 1.05;
+/// Loss thresholds for the parallel loss-based estimate.
+const LOSS_INCREASE_THRESHOLD: f64 = 0.02;
+const LOSS_DECREASE_THRESHOLD: f64 = 0.10;
+/// Adaptive overuse threshold gamma, in milliseconds of estimated delay.
+const GAMMA_INIT: f64 = 12.5;
+const K_UP: f64 = 0.01;
+const K_DOWN: f64 = 0.00018;
+
+/// Bandwidth-usage signal produced by the delay-based detector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Usage {
+    Normal,
+    Overuse,
+    Underuse,
+}
+
+/// A single TWCC-reported packet: when it was sent and when it arrived, both in
+/// microseconds on their respective clocks.
+#[derive(Debug, Clone, Copy)]
+pub struct PacketFeedback {
+    pub send_time_us: i64,
+    pub arrival_time_us: i64,
+}
+
+/// The delay-based and loss-based estimator for a single peer connection.
+pub struct BandwidthEstimator {
+    min_bitrate: u32,
+    max_bitrate: u32,
+    target_bitrate: u32,
+
+    // Delay-based state.
+    accumulated_delay_ms: f64,
+    samples: VecDeque<(f64, f64)>, // (group index, accumulated delay)
+    group_index: f64,
+    last_group: Option<GroupInfo>,
+    gamma_ms: f64,
+    overuse_since: Option<usize>,
+
+    // Loss-based state.
+    packets_in_window: u32,
+    packets_lost_in_window: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GroupInfo {
+    first_send_us: i64,
+    last_send_us: i64,
+    last_arrival_us: i64,
+}
+
+impl BandwidthEstimator {
+    pub fn new(min_bitrate: u32, max_bitrate: u32) -> Self {
+        Self {
+            min_bitrate,
+            max_bitrate,
+            target_bitrate: max_bitrate,
+            accumulated_delay_ms: 0.0,
+            samples: VecDeque::with_capacity(WINDOW_MAX),
+            group_index: 0.0,
+            last_group: None,
+            gamma_ms: GAMMA_INIT,
+            overuse_since: None,
+            packets_in_window: 0,
+            packets_lost_in_window: 0,
+        }
+    }
+
+    /// Feed one batch of TWCC packet feedback. `lost` is the number of packets
+    /// reported missing in the same feedback window. Returns the updated target.
+    pub fn process_feedback(&mut self, packets: &[PacketFeedback], lost: u32) -> u32 {
+        self.packets_in_window += packets.len() as u32 + lost;
+        self.packets_lost_in_window += lost;
+
+        for pkt in packets {
+            self.ingest_packet(*pkt);
+        }
+
+        let delay_target = self.delay_based_target();
+        let loss_target = self.loss_based_target();
+        self.target_bitrate = delay_target.min(loss_target).clamp(self.min_bitrate, self.max_bitrate);
+        self.target_bitrate
+    }
+
+    /// Group the packet into a send burst and, on a group boundary, accumulate
+    /// the inter-group delay variation d(i).
+    fn ingest_packet(&mut self, pkt: PacketFeedback) {
+        match &mut self.last_group {
+            Some(group) if pkt.send_time_us - group.first_send_us < BURST_TIME_US => {
+                group.last_send_us = pkt.send_time_us;
+                group.last_arrival_us = pkt.arrival_time_us;
+            }
+            Some(group) => {
+                let prev = *group;
+                let send_delta = (pkt.send_time_us - prev.last_send_us) as f64 / 1000.0;
+                let arrival_delta = (pkt.arrival_time_us - prev.last_arrival_us) as f64 / 1000.0;
+                let d = arrival_delta - send_delta;
+
+                self.accumulated_delay_ms += d;
+                self.group_index += 1.0;
+                self.samples.push_back((self.group_index, self.accumulated_delay_ms));
+                while self.samples.len() > WINDOW_MAX {
+                    self.samples.pop_front();
+                }
+
+                self.last_group =
+                    Some(GroupInfo { first_send_us: pkt.send_time_us, last_send_us: pkt.send_time_us, last_arrival_us: pkt.arrival_time_us });
+            }
+            None => {
+                self.last_group =
+                    Some(GroupInfo { first_send_us: pkt.send_time_us, last_send_us: pkt.send_time_us, last_arrival_us: pkt.arrival_time_us });
+            }
+        }
+    }
+
+    /// Classify the link from the regression slope over the sliding window.
+    fn detect(&mut self) -> Usage {
+        if self.samples.len() < WINDOW_MIN {
+            return Usage::Normal;
+        }
+
+        let n = self.samples.len() as f64;
+        let (mut sx, mut sy, mut sxx, mut sxy) = (0.0, 0.0, 0.0, 0.0);
+        for &(x, y) in &self.samples {
+            sx += x;
+            sy += y;
+            sxx += x * x;
+            sxy += x * y;
+        }
+        let denom = n * sxx - sx * sx;
+        if denom.abs() < f64::EPSILON {
+            return Usage::Normal;
+        }
+        let slope = (n * sxy - sx * sy) / denom;
+        let estimate = slope * n;
+
+        // Adapt gamma towards the current estimate so transient spikes do not
+        // immediately trip an overuse signal.
+        let k = if estimate.abs() > self.gamma_ms { K_UP } else { K_DOWN };
+        self.gamma_ms += (estimate.abs() - self.gamma_ms) * k;
+
+        if estimate > self.gamma_ms {
+            Usage::Overuse
+        } else if estimate < -self.gamma_ms {
+            Usage::Underuse
+        } else {
+            Usage::Normal
+        }
+    }
+
+    fn delay_based_target(&mut self) -> u32 {
+        match self.detect() {
+            Usage::Overuse => ((self.target_bitrate as f64) * DECREASE_FACTOR) as u32,
+            Usage::Normal => ((self.target_bitrate as f64) * INCREASE_FACTOR) as u32,
+            Usage::Underuse => self.target_bitrate,
+        }
+    }
+
+    fn loss_based_target(&mut self) -> u32 {
+        if self.packets_in_window == 0 {
+            return self.target_bitrate;
+        }
+        let loss = self.packets_lost_in_window as f64 / self.packets_in_window as f64;
+        let target = if loss > LOSS_DECREASE_THRESHOLD {
+            (self.target_bitrate as f64) * (1.0 - 0.5 * loss)
+        } else if loss < LOSS_INCREASE_THRESHOLD {
+            (self.target_bitrate as f64) * INCREASE_FACTOR
+        } else {
+            self.target_bitrate as f64
+        };
+        self.packets_in_window = 0;
+        self.packets_lost_in_window = 0;
+        target as u32
+    }
+
+    pub fn target_bitrate(&self) -> u32 {
+        self.target_bitrate
+    }
+}
@@ -0,0 +1,130 @@
+// Send-time recording for transport-wide congestion control.
+//
+// The GCC delay estimator in `congestion` needs the real send schedule of each
+// packet, keyed by the TWCC transport sequence number the receiver echoes back in
+// its RTCP feedback. webrtc-rs assigns those sequence numbers inside the default
+// TWCC sender interceptor and does not expose them, so we add a tiny recording
+// interceptor of our own: on the outbound path it reads the transport-wide-cc
+// header extension each packet already carries and stores `(sequence, send_time)`
+// in a shared history. The congestion loop in `server` then correlates arrival
+// feedback against this history instead of synthesising a fixed send cadence.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use webrtc::interceptor::stream_info::StreamInfo;
+use webrtc::interceptor::{
+    Attributes, Error as InterceptorError, Interceptor, InterceptorBuilder, RTCPReader, RTCPWriter, RTPReader, RTPWriter,
+};
+
+use crate::websink::server::TWCC_URI;
+
+/// Shared map from TWCC transport sequence number to send time (microseconds on a
+/// local monotonic clock). Bounded so a long-lived session does not grow it
+/// without limit.
+pub type SendHistory = Arc<Mutex<BTreeMap<u16, i64>>>;
+
+/// Keep at most this many recent send records; one second of video at 60 fps with
+/// several packets per frame stays well under it.
+const MAX_HISTORY: usize = 2048;
+
+/// Record a send time, evicting the oldest entries once the history is full.
+pub fn record_send(history: &SendHistory, seq: u16, send_time_us: i64) {
+    let mut map = history.lock().unwrap();
+    map.insert(seq, send_time_us);
+    while map.len() > MAX_HISTORY {
+        // Sequence numbers wrap at u16::MAX, so the oldest entry is the one with the
+        // earliest send time, not the lowest key.
+        if let Some(oldest) = map.iter().min_by_key(|&(_, &t)| t).map(|(&seq, _)| seq) {
+            map.remove(&oldest);
+        } else {
+            break;
+        }
+    }
+}
+
+/// Interceptor builder registered alongside the webrtc-rs defaults.
+pub struct SendTimeRecorderBuilder {
+    history: SendHistory,
+}
+
+impl SendTimeRecorderBuilder {
+    pub fn new(history: SendHistory) -> Self {
+        Self { history }
+    }
+}
+
+impl InterceptorBuilder for SendTimeRecorderBuilder {
+    fn build(&self, _id: &str) -> Result<Arc<dyn Interceptor + Send + Sync>, InterceptorError> {
+        Ok(Arc::new(SendTimeRecorder { history: self.history.clone(), base: Instant::now() }))
+    }
+}
+
+struct SendTimeRecorder {
+    history: SendHistory,
+    base: Instant,
+}
+
+#[async_trait]
+impl Interceptor for SendTimeRecorder {
+    async fn bind_rtcp_reader(&self, reader: Arc<dyn RTCPReader + Send + Sync>) -> Arc<dyn RTCPReader + Send + Sync> {
+        reader
+    }
+
+    async fn bind_rtcp_writer(&self, writer: Arc<dyn RTCPWriter + Send + Sync>) -> Arc<dyn RTCPWriter + Send + Sync> {
+        writer
+    }
+
+    async fn bind_local_stream(
+        &self,
+        info: &StreamInfo,
+        writer: Arc<dyn RTPWriter + Send + Sync>,
+    ) -> Arc<dyn RTPWriter + Send + Sync> {
+        // Find the transport-cc extension id negotiated for this stream; without it
+        // we cannot key the history, so fall back to a transparent pass-through.
+        let twcc_id = info.rtp_header_extensions.iter().find(|e| e.uri == TWCC_URI).map(|e| e.id as u8);
+        Arc::new(RecordingWriter { next: writer, history: self.history.clone(), base: self.base, twcc_id })
+    }
+
+    async fn unbind_local_stream(&self, _info: &StreamInfo) {}
+
+    async fn bind_remote_stream(
+        &self,
+        _info: &StreamInfo,
+        reader: Arc<dyn RTPReader + Send + Sync>,
+    ) -> Arc<dyn RTPReader + Send + Sync> {
+        reader
+    }
+
+    async fn unbind_remote_stream(&self, _info: &StreamInfo) {}
+
+    async fn close(&self) -> Result<(), InterceptorError> {
+        Ok(())
+    }
+}
+
+struct RecordingWriter {
+    next: Arc<dyn RTPWriter + Send + Sync>,
+    history: SendHistory,
+    base: Instant,
+    twcc_id: Option<u8>,
+}
+
+#[async_trait]
+impl RTPWriter for RecordingWriter {
+    async fn write(&self, pkt: &webrtc::rtp::packet::Packet, attributes: &Attributes) -> Result<usize, InterceptorError> {
+        if let Some(id) = self.twcc_id {
+            // The transport-cc extension payload is a 2-byte big-endian sequence
+            // number stamped by the default sender interceptor upstream of us.
+            if let Some(ext) = pkt.header.get_extension(id) {
+                if ext.len() >= 2 {
+                    let seq = u16::from_be_bytes([ext[0], ext[1]]);
+                    record_send(&self.history, seq, self.base.elapsed().as_micros() as i64);
+                }
+            }
+        }
+        self.next.write(pkt, attributes).await
+    }
+}
@@ -11,7 +11,7 @@ use std::time::Duration;
 use bytes;
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc;
-use webrtc::api::media_engine::{MIME_TYPE_H264, MIME_TYPE_HEVC, MIME_TYPE_VP8, MIME_TYPE_VP9};
+use webrtc::api::media_engine::{MIME_TYPE_AV1, MIME_TYPE_H264, MIME_TYPE_HEVC, MIME_TYPE_OPUS, MIME_TYPE_VP8, MIME_TYPE_VP9};
 use webrtc::ice_transport::ice_server::RTCIceServer;
 use webrtc::media::Sample;
 use webrtc::peer_connection::configuration::RTCConfiguration;
@@ -34,6 +34,7 @@ pub enum VideoCodec {
     H265,
     VP8,
     VP9,
+    AV1,
 }
 
 // Stream mode enumeration
@@ -43,6 +44,46 @@ pub enum StreamMode {
     Rtp,
 }
 
+// Audio codec enumeration. Opus is the only WebRTC-mandated audio codec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioCodec {
+    Opus,
+}
+
+impl AudioCodec {
+    /// Get the MIME type for WebRTC
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            AudioCodec::Opus => MIME_TYPE_OPUS,
+        }
+    }
+
+    /// Detect codec and stream mode from GStreamer caps
+    pub fn from_caps(caps: &gst::Caps) -> Option<(Self, StreamMode)> {
+        let structure = caps.structure(0)?;
+        match structure.name().as_str() {
+            "audio/x-opus" => Some((AudioCodec::Opus, StreamMode::Sample)),
+            "application/x-rtp" => {
+                let encoding_name = structure.get::<String>("encoding-name").ok()?;
+                // WebRTC only carries Opus at its canonical 48 kHz RTP clock rate.
+                let clock_rate = structure.get::<i32>("clock-rate").unwrap_or(48000);
+                match encoding_name.as_str() {
+                    "OPUS" if clock_rate == 48000 => Some((AudioCodec::Opus, StreamMode::Rtp)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Get human-readable codec name
+    pub fn name(&self) -> &'static str {
+        match self {
+            AudioCodec::Opus => "Opus",
+        }
+    }
+}
+
 impl VideoCodec {
     /// Get the MIME type for WebRTC
     pub fn mime_type(&self) -> &'static str {
@@ -51,6 +92,7 @@ impl VideoCodec {
             VideoCodec::H265 => MIME_TYPE_HEVC,
             VideoCodec::VP8 => MIME_TYPE_VP8,
             VideoCodec::VP9 => MIME_TYPE_VP9,
+            VideoCodec::AV1 => MIME_TYPE_AV1,
         }
     }
 
@@ -64,6 +106,7 @@ impl VideoCodec {
             "video/x-h265" => Some((VideoCodec::H265, StreamMode::Sample)),
             "video/x-vp8" => Some((VideoCodec::VP8, StreamMode::Sample)),
             "video/x-vp9" => Some((VideoCodec::VP9, StreamMode::Sample)),
+            "video/x-av1" => Some((VideoCodec::AV1, StreamMode::Sample)),
             "application/x-rtp" => {
                 let encoding_name = structure.get::<String>("encoding-name").ok()?;
                 match encoding_name.as_str() {
@@ -71,6 +114,7 @@ impl VideoCodec {
                     "H265" => Some((VideoCodec::H265, StreamMode::Rtp)),
                     "VP8" => Some((VideoCodec::VP8, StreamMode::Rtp)),
                     "VP9" => Some((VideoCodec::VP9, StreamMode::Rtp)),
+                    "AV1" => Some((VideoCodec::AV1, StreamMode::Rtp)),
                     _ => None,
                 }
             }
@@ -85,6 +129,7 @@ impl VideoCodec {
             VideoCodec::H265 => "H.265/HEVC",
             VideoCodec::VP8 => "VP8",
             VideoCodec::VP9 => "VP9",
+            VideoCodec::AV1 => "AV1",
         }
     }
 }
@@ -92,6 +137,40 @@ impl VideoCodec {
 // Default values for properties
 const DEFAULT_PORT: u16 = 8091;
 const DEFAULT_STUN_SERVER: &str = "stun:stun.l.google.com:19302";
+const DEFAULT_MIN_BITRATE: u32 = 100_000;
+const DEFAULT_MAX_BITRATE: u32 = 8_000_000;
+const DEFAULT_CLOCK: &str = "system";
+const DEFAULT_NTP_SERVER: &str = "pool.ntp.org:123";
+const DEFAULT_PTP_DOMAIN: u32 = 0;
+const DEFAULT_CLOCK_SYNC_TIMEOUT: u64 = 0;
+
+// Reference-clock selection for RFC 7273 signalling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockKind {
+    System,
+    Ntp,
+    Ptp,
+}
+
+impl ClockKind {
+    /// Parse the `clock` element property, falling back to `System` for any
+    /// unrecognised value.
+    fn from_property_str(s: &str) -> Self {
+        match s {
+            "ntp" => ClockKind::Ntp,
+            "ptp" => ClockKind::Ptp,
+            _ => ClockKind::System,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            ClockKind::System => "system",
+            ClockKind::Ntp => "ntp",
+            ClockKind::Ptp => "ptp",
+        }
+    }
+}
 
 // Property value storage
 #[derive(Debug, Clone)]
@@ -99,11 +178,45 @@ struct Settings {
     port: u16,
     stun_server: String,
     is_live: bool,
+    min_bitrate: u32,
+    max_bitrate: u32,
+    enable_navigation: bool,
+    certificate_file: Option<String>,
+    private_key_file: Option<String>,
+    clock: ClockKind,
+    ntp_server: String,
+    ptp_domain: u32,
+    clock_sync_timeout: u64,
+    do_clock_signalling: bool,
+    turn_server: Option<String>,
+    ice_servers: String,
+    ice_network_types: String,
+    host_ip: Option<String>,
+    signaller: crate::websink::signaller::SignallerKind,
 }
 
 impl Default for Settings {
     fn default() -> Self {
-        Self { port: DEFAULT_PORT, stun_server: String::from(DEFAULT_STUN_SERVER), is_live: false }
+        Self {
+            port: DEFAULT_PORT,
+            stun_server: String::from(DEFAULT_STUN_SERVER),
+            is_live: false,
+            min_bitrate: DEFAULT_MIN_BITRATE,
+            max_bitrate: DEFAULT_MAX_BITRATE,
+            enable_navigation: false,
+            certificate_file: None,
+            private_key_file: None,
+            clock: ClockKind::System,
+            ntp_server: String::from(DEFAULT_NTP_SERVER),
+            ptp_domain: DEFAULT_PTP_DOMAIN,
+            clock_sync_timeout: DEFAULT_CLOCK_SYNC_TIMEOUT,
+            do_clock_signalling: false,
+            turn_server: None,
+            ice_servers: String::new(),
+            ice_network_types: String::new(),
+            host_ip: None,
+            signaller: crate::websink::signaller::SignallerKind::Http,
+        }
     }
 }
 
@@ -115,12 +228,19 @@ pub struct WebSink {
     settings: Mutex<Settings>,
     state: Arc<Mutex<State>>,
     render_count: AtomicU32,
+    // The optional requested audio sink pad, if any.
+    audio_pad: Mutex<Option<gst::Pad>>,
 }
 
 // Default implementation for our element
 impl Default for WebSink {
     fn default() -> Self {
-        Self { settings: Mutex::new(Settings::default()), state: Arc::new(Mutex::new(State::default())), render_count: AtomicU32::new(0) }
+        Self {
+            settings: Mutex::new(Settings::default()),
+            state: Arc::new(Mutex::new(State::default())),
+            render_count: AtomicU32::new(0),
+            audio_pad: Mutex::new(None),
+        }
     }
 }
 
@@ -155,12 +275,102 @@ impl ObjectImpl for WebSink {
                     .blurb("Whether to block Render without peers (default: false)")
                     .default_value(false)
                     .build(),
+                glib::ParamSpecUInt::builder("min-bitrate")
+                    .nick("Minimum Bitrate")
+                    .blurb("Lower clamp for the congestion-controlled encoder bitrate, in bits/s")
+                    .minimum(1)
+                    .maximum(u32::MAX)
+                    .default_value(DEFAULT_MIN_BITRATE)
+                    .build(),
+                glib::ParamSpecUInt::builder("max-bitrate")
+                    .nick("Maximum Bitrate")
+                    .blurb("Upper clamp for the congestion-controlled encoder bitrate, in bits/s")
+                    .minimum(1)
+                    .maximum(u32::MAX)
+                    .default_value(DEFAULT_MAX_BITRATE)
+                    .build(),
+                glib::ParamSpecBoolean::builder("enable-navigation")
+                    .nick("Enable Navigation")
+                    .blurb("Forward browser input events upstream as navigation events (default: false)")
+                    .default_value(false)
+                    .build(),
+                glib::ParamSpecString::builder("certificate-file")
+                    .nick("TLS Certificate File")
+                    .blurb("PEM certificate chain; enables HTTPS/WSS when set together with private-key-file")
+                    .build(),
+                glib::ParamSpecString::builder("private-key-file")
+                    .nick("TLS Private Key File")
+                    .blurb("PEM private key matching certificate-file")
+                    .build(),
+                glib::ParamSpecString::builder("clock")
+                    .nick("Reference Clock")
+                    .blurb("Pipeline reference clock: system, ntp or ptp")
+                    .default_value(DEFAULT_CLOCK)
+                    .build(),
+                glib::ParamSpecString::builder("ntp-server")
+                    .nick("NTP Server")
+                    .blurb("NTP server (host:port) used when clock=ntp")
+                    .default_value(DEFAULT_NTP_SERVER)
+                    .build(),
+                glib::ParamSpecUInt::builder("ptp-domain")
+                    .nick("PTP Domain")
+                    .blurb("PTP domain used when clock=ptp")
+                    .minimum(0)
+                    .maximum(127)
+                    .default_value(DEFAULT_PTP_DOMAIN)
+                    .build(),
+                glib::ParamSpecUInt64::builder("clock-sync-timeout")
+                    .nick("Clock Sync Timeout")
+                    .blurb("Nanoseconds to wait for the reference clock to synchronize (0 = no wait)")
+                    .default_value(DEFAULT_CLOCK_SYNC_TIMEOUT)
+                    .build(),
+                glib::ParamSpecBoolean::builder("do-clock-signalling")
+                    .nick("Do Clock Signalling")
+                    .blurb("Emit RFC 7273 clock and RFC 6051 rapid-sync SDP attributes/extensions")
+                    .default_value(false)
+                    .build(),
+                glib::ParamSpecString::builder("turn-server")
+                    .nick("TURN Server")
+                    .blurb("TURN server as turn(s)://user:pass@host:port (empty for none)")
+                    .build(),
+                glib::ParamSpecString::builder("ice-servers")
+                    .nick("ICE Servers")
+                    .blurb("JSON array of ICE servers, each {\"urls\":[...], \"username\":..., \"credential\":...}")
+                    .default_value("")
+                    .build(),
+                glib::ParamSpecString::builder("ice-network-types")
+                    .nick("ICE Network Types")
+                    .blurb("Comma-separated candidate network types to gather: udp4, udp6, tcp4, tcp6 (empty for all)")
+                    .default_value("")
+                    .build(),
+                glib::ParamSpecString::builder("host-ip")
+                    .nick("Host Candidate IP")
+                    .blurb("Fixed 1:1 NAT host candidate IP to advertise (empty to auto-detect)")
+                    .build(),
+                glib::ParamSpecString::builder("signaller")
+                    .nick("Signalling Backend")
+                    .blurb("Signalling transport: http (POST offer/answer) or websocket (trickle ICE)")
+                    .default_value("http")
+                    .build(),
             ]
         });
 
         PROPERTIES.as_ref()
     }
 
+    fn signals() -> &'static [glib::subclass::Signal] {
+        use once_cell::sync::Lazy;
+        static SIGNALS: Lazy<Vec<glib::subclass::Signal>> = Lazy::new(|| {
+            vec![
+                // Emitted when congestion control recommends a new target bitrate (in
+                // bits/s). After emitting it the element still retargets a known
+                // upstream encoder (x264enc/x265enc/vp8enc/vp9enc) itself.
+                glib::subclass::Signal::builder("bitrate").param_types([u32::static_type()]).build(),
+            ]
+        });
+        SIGNALS.as_ref()
+    }
+
     fn set_property(&self, _id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
         match pspec.name() {
             "port" => {
@@ -182,6 +392,68 @@ impl ObjectImpl for WebSink {
                 gst::info!(CAT, "Changing is-live from {} to {}", settings.is_live, is_live);
                 settings.is_live = is_live;
             }
+            "min-bitrate" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.min_bitrate = value.get::<u32>().expect("type checked upstream");
+            }
+            "max-bitrate" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.max_bitrate = value.get::<u32>().expect("type checked upstream");
+            }
+            "enable-navigation" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.enable_navigation = value.get::<bool>().expect("type checked upstream");
+            }
+            "certificate-file" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.certificate_file = value.get::<Option<String>>().expect("type checked upstream").filter(|s| !s.is_empty());
+            }
+            "private-key-file" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.private_key_file = value.get::<Option<String>>().expect("type checked upstream").filter(|s| !s.is_empty());
+            }
+            "clock" => {
+                let mut settings = self.settings.lock().unwrap();
+                let s = value.get::<String>().expect("type checked upstream");
+                settings.clock = ClockKind::from_property_str(&s);
+            }
+            "ntp-server" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.ntp_server = value.get::<String>().expect("type checked upstream");
+            }
+            "ptp-domain" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.ptp_domain = value.get::<u32>().expect("type checked upstream");
+            }
+            "clock-sync-timeout" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.clock_sync_timeout = value.get::<u64>().expect("type checked upstream");
+            }
+            "do-clock-signalling" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.do_clock_signalling = value.get::<bool>().expect("type checked upstream");
+            }
+            "turn-server" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.turn_server = value.get::<Option<String>>().expect("type checked upstream").filter(|s| !s.is_empty());
+            }
+            "ice-servers" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.ice_servers = value.get::<String>().expect("type checked upstream");
+            }
+            "ice-network-types" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.ice_network_types = value.get::<String>().expect("type checked upstream");
+            }
+            "host-ip" => {
+                let mut settings = self.settings.lock().unwrap();
+                settings.host_ip = value.get::<Option<String>>().expect("type checked upstream").filter(|s| !s.is_empty());
+            }
+            "signaller" => {
+                let mut settings = self.settings.lock().unwrap();
+                let s = value.get::<String>().expect("type checked upstream");
+                settings.signaller = crate::websink::signaller::SignallerKind::from_property_str(&s);
+            }
             _ => unimplemented!(),
         }
     }
@@ -200,6 +472,66 @@ impl ObjectImpl for WebSink {
                 let settings = self.settings.lock().unwrap();
                 settings.is_live.to_value()
             }
+            "min-bitrate" => {
+                let settings = self.settings.lock().unwrap();
+                settings.min_bitrate.to_value()
+            }
+            "max-bitrate" => {
+                let settings = self.settings.lock().unwrap();
+                settings.max_bitrate.to_value()
+            }
+            "enable-navigation" => {
+                let settings = self.settings.lock().unwrap();
+                settings.enable_navigation.to_value()
+            }
+            "certificate-file" => {
+                let settings = self.settings.lock().unwrap();
+                settings.certificate_file.to_value()
+            }
+            "private-key-file" => {
+                let settings = self.settings.lock().unwrap();
+                settings.private_key_file.to_value()
+            }
+            "clock" => {
+                let settings = self.settings.lock().unwrap();
+                settings.clock.as_str().to_value()
+            }
+            "ntp-server" => {
+                let settings = self.settings.lock().unwrap();
+                settings.ntp_server.to_value()
+            }
+            "ptp-domain" => {
+                let settings = self.settings.lock().unwrap();
+                settings.ptp_domain.to_value()
+            }
+            "clock-sync-timeout" => {
+                let settings = self.settings.lock().unwrap();
+                settings.clock_sync_timeout.to_value()
+            }
+            "do-clock-signalling" => {
+                let settings = self.settings.lock().unwrap();
+                settings.do_clock_signalling.to_value()
+            }
+            "turn-server" => {
+                let settings = self.settings.lock().unwrap();
+                settings.turn_server.to_value()
+            }
+            "ice-servers" => {
+                let settings = self.settings.lock().unwrap();
+                settings.ice_servers.to_value()
+            }
+            "ice-network-types" => {
+                let settings = self.settings.lock().unwrap();
+                settings.ice_network_types.to_value()
+            }
+            "host-ip" => {
+                let settings = self.settings.lock().unwrap();
+                settings.host_ip.to_value()
+            }
+            "signaller" => {
+                let settings = self.settings.lock().unwrap();
+                settings.signaller.as_str().to_value()
+            }
             _ => unimplemented!(),
         }
     }
@@ -232,11 +564,12 @@ impl ElementImpl for WebSink {
             let h265_caps = gst::Caps::builder("video/x-h265").field("stream-format", "byte-stream").field("alignment", "au").build();
             let vp8_caps = gst::Caps::builder("video/x-vp8").build();
             let vp9_caps = gst::Caps::builder("video/x-vp9").build();
+            let av1_caps = gst::Caps::builder("video/x-av1").build();
 
             // RTP caps for all supported codecs
             let rtp_caps = gst::Caps::builder("application/x-rtp")
                 .field("media", "video")
-                .field("encoding-name", gst::List::new(["H264", "H265", "VP8", "VP9"]))
+                .field("encoding-name", gst::List::new(["H264", "H265", "VP8", "VP9", "AV1"]))
                 .field("clock-rate", 90000)
                 .build();
 
@@ -244,16 +577,106 @@ impl ElementImpl for WebSink {
             combined_caps.merge(h265_caps);
             combined_caps.merge(vp8_caps);
             combined_caps.merge(vp9_caps);
+            combined_caps.merge(av1_caps);
             combined_caps.merge(rtp_caps);
 
             let sink_pad_template =
                 gst::PadTemplate::new("sink", gst::PadDirection::Sink, gst::PadPresence::Always, &combined_caps).unwrap();
 
-            vec![sink_pad_template]
+            // Optional audio pad: raw Opus or RTP-packetised Opus.
+            let opus_caps = gst::Caps::builder("audio/x-opus").build();
+            let rtp_opus_caps = gst::Caps::builder("application/x-rtp")
+                .field("media", "audio")
+                .field("encoding-name", "OPUS")
+                .field("clock-rate", 48000)
+                .build();
+            let mut audio_caps = opus_caps;
+            audio_caps.merge(rtp_opus_caps);
+
+            let audio_pad_template =
+                gst::PadTemplate::new("audio", gst::PadDirection::Sink, gst::PadPresence::Request, &audio_caps).unwrap();
+
+            vec![sink_pad_template, audio_pad_template]
         });
 
         PAD_TEMPLATES.as_ref()
     }
+
+    fn request_new_pad(
+        &self,
+        templ: &gst::PadTemplate,
+        _name: Option<&str>,
+        _caps: Option<&gst::Caps>,
+    ) -> Option<gst::Pad> {
+        if templ.name_template() != "audio" {
+            return None;
+        }
+
+        let mut audio_pad = self.audio_pad.lock().unwrap();
+        if audio_pad.is_some() {
+            gst::warning!(CAT, "Only a single audio pad is supported");
+            return None;
+        }
+
+        let pad = gst::Pad::builder_from_template(templ)
+            .chain_function(|pad, parent, buffer| {
+                WebSink::catch_panic_pad_function(
+                    parent,
+                    || Err(gst::FlowError::Error),
+                    |this| this.chain_audio(pad, buffer),
+                )
+            })
+            .event_function(|pad, parent, event| {
+                WebSink::catch_panic_pad_function(parent, || false, |this| this.audio_event(pad, event))
+            })
+            .build();
+
+        pad.set_active(true).ok()?;
+        self.obj().add_pad(&pad).ok()?;
+        gst::info!(CAT, "🔊 Audio pad requested");
+        *audio_pad = Some(pad.clone());
+        Some(pad)
+    }
+
+    fn release_pad(&self, pad: &gst::Pad) {
+        let _ = pad.set_active(false);
+        let _ = self.obj().remove_pad(pad);
+        let mut audio_pad = self.audio_pad.lock().unwrap();
+        if audio_pad.as_ref() == Some(pad) {
+            *audio_pad = None;
+        }
+        let mut state = self.state.lock().unwrap();
+        state.audio_track = None;
+        gst::info!(CAT, "🔊 Audio pad released");
+    }
+
+    fn provide_clock(&self) -> Option<gst::Clock> {
+        let settings = self.settings.lock().unwrap();
+        let clock: gst::Clock = match settings.clock {
+            ClockKind::System => return None, // let the pipeline pick its default clock
+            ClockKind::Ntp => {
+                let (host, port) = parse_host_port(&settings.ntp_server, 123);
+                gst::info!(CAT, "⏰ Providing NTP clock from {}:{}", host, port);
+                gst_net::NtpClock::new(None, &host, port, gst::ClockTime::ZERO).upcast()
+            }
+            ClockKind::Ptp => {
+                gst::info!(CAT, "⏰ Providing PTP clock on domain {}", settings.ptp_domain);
+                gst_net::PtpClock::new(None, settings.ptp_domain).upcast()
+            }
+        };
+
+        let timeout = settings.clock_sync_timeout;
+        drop(settings);
+
+        if timeout > 0 {
+            gst::info!(CAT, "⏳ Waiting up to {} ns for reference clock to synchronize", timeout);
+            if clock.wait_for_sync(gst::ClockTime::from_nseconds(timeout)).is_err() {
+                gst::warning!(CAT, "⚠️ Reference clock did not synchronize within timeout");
+            }
+        }
+
+        Some(clock)
+    }
 }
 
 // Implementation of BaseSink methods
@@ -267,11 +690,38 @@ impl BaseSinkImpl for WebSink {
 
         gst::info!(CAT, "🎥 Detected codec: {} in {:?} mode", codec.name(), mode);
 
-        // Create or update video track if we have a runtime
+        // Remember the negotiated resolution so navigation coordinates can be
+        // denormalised against it. Encoded/RTP caps may omit these.
+        if let Some(structure) = caps.structure(0) {
+            let width = structure.get::<i32>("width").ok();
+            let height = structure.get::<i32>("height").ok();
+            if let (Some(width), Some(height)) = (width, height) {
+                let mut state = self.state.lock().unwrap();
+                state.video_width = width as u32;
+                state.video_height = height as u32;
+            }
+        }
+
+        // Create or update the video track if we have a runtime.
         let state_guard = self.state.lock().unwrap();
         if state_guard.runtime.is_some() {
+            // A codec or stream-mode change needs a brand-new track (and peer
+            // renegotiation); a resolution/framerate change within the same codec
+            // is tolerated by WebRTC, so the existing track is left untouched.
+            let needs_new_track = match &state_guard.video_track {
+                Some(existing) => {
+                    existing.codec_mime_type() != codec.mime_type() || existing.is_rtp() != matches!(mode, StreamMode::Rtp)
+                }
+                None => true,
+            };
             drop(state_guard);
-            self.create_video_track(codec, mode)?;
+
+            if needs_new_track {
+                self.create_video_track(codec, mode)?;
+                server::renegotiate_video_track(&self.state);
+            } else {
+                gst::info!(CAT, "🎞️ Caps changed within {} {:?}; keeping existing track", codec.name(), mode);
+            }
         }
 
         Ok(())
@@ -305,20 +755,99 @@ impl BaseSinkImpl for WebSink {
         // Configure WebRTC
         let settings = self.settings.lock().unwrap();
         let mut webrtc_config = RTCConfiguration::default();
+        let mut ice_servers = Vec::new();
         if !settings.stun_server.is_empty() {
-            webrtc_config.ice_servers = vec![RTCIceServer { urls: vec![settings.stun_server.clone()], ..Default::default() }];
+            ice_servers.push(RTCIceServer { urls: vec![settings.stun_server.clone()], ..Default::default() });
             gst::info!(CAT, "🌐 STUN server configured: {}", settings.stun_server);
-        } else {
-            gst::info!(CAT, "⚠️ No STUN server configured");
         }
+        if let Some(turn) = &settings.turn_server {
+            match parse_turn_uri(turn) {
+                Some(server) => {
+                    gst::info!(CAT, "🌐 TURN server configured: {:?}", server.urls);
+                    ice_servers.push(server);
+                }
+                None => gst::warning!(CAT, "⚠️ Could not parse turn-server URI: {}", turn),
+            }
+        }
+        if !settings.ice_servers.is_empty() {
+            match parse_ice_servers_json(&settings.ice_servers) {
+                Some(servers) => {
+                    gst::info!(CAT, "🌐 {} ICE server(s) configured from ice-servers", servers.len());
+                    ice_servers.extend(servers);
+                }
+                None => gst::warning!(CAT, "⚠️ Could not parse ice-servers JSON; ignoring"),
+            }
+        }
+        webrtc_config.ice_servers = ice_servers;
         let port = settings.port;
+        let min_bitrate = settings.min_bitrate;
+        let max_bitrate = settings.max_bitrate;
+        let enable_navigation = settings.enable_navigation;
+        let tls = match (&settings.certificate_file, &settings.private_key_file) {
+            (Some(cert), Some(key)) => Some(server::TlsConfig { certificate_file: cert.clone(), private_key_file: key.clone() }),
+            _ => None,
+        };
+        let ice_network_types =
+            settings.ice_network_types.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect::<Vec<_>>();
+        let host_ip = settings.host_ip.clone();
+        let signaller = settings.signaller;
+        let clock_signalling = settings.do_clock_signalling.then(|| server::ClockSignalling {
+            kind: settings.clock.as_str().to_string(),
+            ntp_server: settings.ntp_server.clone(),
+            ptp_domain: settings.ptp_domain,
+        });
         drop(settings);
 
+        // Channel carrying congestion-control bitrate estimates back to the element.
+        let (bitrate_tx, bitrate_rx) = mpsc::channel(1);
+        // Channel carrying browser input events back to the element.
+        let (nav_tx, nav_rx) = mpsc::channel(64);
+
         let mut state = self.state.lock().unwrap();
         state.runtime = Some(runtime);
         state.unblock_tx = Some(tx);
         state.unblock_rx = Some(rx);
         state.webrtc_config = Some(webrtc_config);
+        state.min_bitrate = min_bitrate;
+        state.max_bitrate = max_bitrate;
+        state.bitrate_tx = Some(bitrate_tx);
+        state.bitrate_rx = Some(bitrate_rx);
+        state.enable_navigation = enable_navigation;
+        state.nav_tx = Some(nav_tx);
+        state.nav_rx = Some(nav_rx);
+        state.tls = tls;
+        state.clock_signalling = clock_signalling;
+        state.ice_network_types = ice_network_types;
+        state.host_ip = host_ip;
+        state.signaller = signaller;
+
+        // Drain browser input events and push them upstream as navigation events.
+        if enable_navigation {
+            if let Some(mut nav_rx) = state.nav_rx.take() {
+                let element = self.obj().clone();
+                let rt = state.runtime.as_ref().expect("Runtime should be initialized");
+                rt.spawn(async move {
+                    while let Some(event) = nav_rx.recv().await {
+                        element.imp().push_navigation_event(event);
+                    }
+                });
+            }
+        }
+
+        // Drain congestion-control estimates and retarget the upstream encoder.
+        if let Some(mut rx) = state.bitrate_rx.take() {
+            let element = self.obj().clone();
+            let rt = state.runtime.as_ref().expect("Runtime should be initialized");
+            rt.spawn(async move {
+                while let Some(bitrate) = rx.recv().await {
+                    // Notify listeners, post a bus message for bwe-request-style
+                    // handlers, then retarget the encoder ourselves.
+                    element.emit_by_name::<()>("bitrate", &[&bitrate]);
+                    element.imp().post_bitrate_message(bitrate);
+                    element.imp().apply_target_bitrate(bitrate);
+                }
+            });
+        }
 
         // Start HTTP server
         gst::info!(CAT, "🌐 Starting HTTP server on port {}", port);
@@ -366,7 +895,14 @@ impl BaseSinkImpl for WebSink {
         state.unblock_rx = None;
         state.runtime = None;
         state.video_track = None;
+        state.audio_track = None;
         state.webrtc_config = None;
+        state.bitrate_tx = None;
+        state.bitrate_rx = None;
+        state.peer_bitrates.clear();
+        state.renegotiation_txs.clear();
+        state.nav_tx = None;
+        state.nav_rx = None;
         gst::debug!(CAT, "🧹 Reset all state components");
 
         gst::info!(CAT, "✅ WebSink stopped successfully");
@@ -401,55 +937,202 @@ impl BaseSinkImpl for WebSink {
 
         if num_peers > 0 {
             let state = self.state.lock().unwrap();
-            if let Some(video_track) = &state.video_track {
-                match video_track {
-                    server::VideoTrack::Sample(track) => {
-                        let track_clone = Arc::clone(track);
-                        let data_copy = bytes::Bytes::copy_from_slice(data);
-                        let duration = buffer.duration().unwrap_or_else(|| gst::ClockTime::from_nseconds(33_333_333));
-
-                        if let Some(runtime) = &state.runtime {
-                            runtime.spawn(async move {
-                                let sample =
-                                    Sample { data: data_copy, duration: Duration::from_nanos(duration.nseconds()), ..Default::default() };
-
-                                if let Err(e) = track_clone.write_sample(&sample).await {
-                                    gst::error!(CAT, "❌ Failed to write sample: {}", e);
-                                }
-                            });
+            if let (Some(video_track), Some(runtime)) = (&state.video_track, &state.runtime) {
+                let is_av1 = matches!(video_track, server::VideoTrack::Rtp(track) if track.codec().mime_type.eq_ignore_ascii_case(MIME_TYPE_AV1));
+                let duration = buffer.duration().unwrap_or_else(|| gst::ClockTime::from_nseconds(33_333_333));
+                spawn_write(runtime, video_track, data, duration, is_av1);
+            }
+        }
+
+        Ok(gst::FlowSuccess::Ok)
+    }
+}
+
+/// Parse a `turn(s)://user:pass@host:port` URI into an `RTCIceServer`, pulling the
+/// embedded credentials out into the username/credential fields webrtc expects.
+fn parse_turn_uri(uri: &str) -> Option<RTCIceServer> {
+    let (scheme, rest) = uri.split_once("://")?;
+    if scheme != "turn" && scheme != "turns" {
+        return None;
+    }
+    let (userinfo, host) = match rest.split_once('@') {
+        Some((userinfo, host)) => (Some(userinfo), host),
+        None => (None, rest),
+    };
+    let (username, credential) = match userinfo {
+        Some(info) => {
+            let (u, c) = info.split_once(':')?;
+            (u.to_string(), c.to_string())
+        }
+        None => (String::new(), String::new()),
+    };
+    Some(RTCIceServer { urls: vec![format!("{}:{}", scheme, host)], username, credential, ..Default::default() })
+}
+
+/// Parse the `ice-servers` property: a JSON array of entries, each with a `urls`
+/// field (a single string or an array of strings) and optional `username` and
+/// `credential` for TURN authentication.
+fn parse_ice_servers_json(json: &str) -> Option<Vec<RTCIceServer>> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Urls {
+        One(String),
+        Many(Vec<String>),
+    }
+    #[derive(serde::Deserialize)]
+    struct Entry {
+        urls: Urls,
+        #[serde(default)]
+        username: String,
+        #[serde(default)]
+        credential: String,
+    }
+    let entries: Vec<Entry> = serde_json::from_str(json).ok()?;
+    Some(
+        entries
+            .into_iter()
+            .map(|e| RTCIceServer {
+                urls: match e.urls {
+                    Urls::One(u) => vec![u],
+                    Urls::Many(u) => u,
+                },
+                username: e.username,
+                credential: e.credential,
+                ..Default::default()
+            })
+            .collect(),
+    )
+}
+
+/// Split a `host:port` string, falling back to `default_port` when no port is given.
+fn parse_host_port(s: &str, default_port: i32) -> (String, i32) {
+    match s.rsplit_once(':') {
+        Some((host, port)) => (host.to_string(), port.parse().unwrap_or(default_port)),
+        None => (s.to_string(), default_port),
+    }
+}
+
+/// Spawn an async write of one encoded buffer to a WebRTC video track. Used by the
+/// video render path; the audio pad's chain function uses `spawn_write_audio`.
+fn spawn_write(runtime: &Runtime, track: &server::VideoTrack, data: &[u8], duration: gst::ClockTime, is_av1: bool) {
+    match track {
+        server::VideoTrack::Sample(track) => {
+            let track_clone = Arc::clone(track);
+            let data_copy = bytes::Bytes::copy_from_slice(data);
+            runtime.spawn(async move {
+                let sample = Sample { data: data_copy, duration: Duration::from_nanos(duration.nseconds()), ..Default::default() };
+                if let Err(e) = track_clone.write_sample(&sample).await {
+                    gst::error!(CAT, "❌ Failed to write sample: {}", e);
+                }
+            });
+        }
+        server::VideoTrack::Rtp(track) => {
+            let track_clone = Arc::clone(track);
+            let data_copy = data.to_vec();
+            runtime.spawn(async move {
+                use util::Unmarshal;
+
+                let mut buf = &data_copy[..];
+                match rtp::packet::Packet::unmarshal(&mut buf) {
+                    Ok(rtp_packet) => {
+                        if is_av1 && crate::websink::av1::parse_aggregation_units(&rtp_packet.payload).is_none() {
+                            gst::warning!(CAT, "⚠️ Dropping unparseable AV1 RTP payload");
+                            return;
                         }
-                    }
-                    server::VideoTrack::Rtp(track) => {
-                        let track_clone = Arc::clone(track);
-                        let data_copy = data.to_vec();
-
-                        if let Some(runtime) = &state.runtime {
-                            runtime.spawn(async move {
-                                use util::Unmarshal;
-
-                                let mut buf = &data_copy[..];
-                                match rtp::packet::Packet::unmarshal(&mut buf) {
-                                    Ok(rtp_packet) => {
-                                        if let Err(e) = track_clone.write_rtp(&rtp_packet).await {
-                                            gst::error!(CAT, "❌ Failed to write RTP packet: {}", e);
-                                        }
-                                    }
-                                    Err(e) => {
-                                        gst::error!(CAT, "❌ Failed to parse RTP packet: {}", e);
-                                    }
-                                }
-                            });
+                        if let Err(e) = track_clone.write_rtp(&rtp_packet).await {
+                            gst::error!(CAT, "❌ Failed to write RTP packet: {}", e);
                         }
                     }
+                    Err(e) => gst::error!(CAT, "❌ Failed to parse RTP packet: {}", e),
                 }
-            }
+            });
         }
+    }
+}
 
-        Ok(gst::FlowSuccess::Ok)
+/// Spawn an async write of one encoded audio buffer to its WebRTC track.
+fn spawn_write_audio(runtime: &Runtime, track: &server::AudioTrack, data: &[u8], duration: gst::ClockTime) {
+    match track {
+        server::AudioTrack::Sample(track) => {
+            let track_clone = Arc::clone(track);
+            let data_copy = bytes::Bytes::copy_from_slice(data);
+            runtime.spawn(async move {
+                let sample = Sample { data: data_copy, duration: Duration::from_nanos(duration.nseconds()), ..Default::default() };
+                if let Err(e) = track_clone.write_sample(&sample).await {
+                    gst::error!(CAT, "❌ Failed to write audio sample: {}", e);
+                }
+            });
+        }
+        server::AudioTrack::Rtp(track) => {
+            let track_clone = Arc::clone(track);
+            let data_copy = data.to_vec();
+            runtime.spawn(async move {
+                use util::Unmarshal;
+                let mut buf = &data_copy[..];
+                match rtp::packet::Packet::unmarshal(&mut buf) {
+                    Ok(rtp_packet) => {
+                        if let Err(e) = track_clone.write_rtp(&rtp_packet).await {
+                            gst::error!(CAT, "❌ Failed to write audio RTP packet: {}", e);
+                        }
+                    }
+                    Err(e) => gst::error!(CAT, "❌ Failed to parse audio RTP packet: {}", e),
+                }
+            });
+        }
     }
 }
 
 impl WebSink {
+    /// Chain function for the audio pad: route Opus buffers to the audio track.
+    fn chain_audio(&self, _pad: &gst::Pad, buffer: gst::Buffer) -> Result<gst::FlowSuccess, gst::FlowError> {
+        let num_peers = {
+            let state = self.state.lock().unwrap();
+            state.peer_connections.len()
+        };
+        if num_peers == 0 {
+            return Ok(gst::FlowSuccess::Ok);
+        }
+
+        let map = buffer.map_readable().map_err(|_| gst::FlowError::Error)?;
+        let state = self.state.lock().unwrap();
+        if let (Some(track), Some(runtime)) = (&state.audio_track, &state.runtime) {
+            let duration = buffer.duration().unwrap_or_else(|| gst::ClockTime::from_nseconds(20_000_000));
+            spawn_write_audio(runtime, track, map.as_slice(), duration);
+        }
+        Ok(gst::FlowSuccess::Ok)
+    }
+
+    /// Event function for the audio pad: create the audio track when caps arrive.
+    fn audio_event(&self, pad: &gst::Pad, event: gst::Event) -> bool {
+        if let gst::EventView::Caps(caps) = event.view() {
+            if let Some((codec, mode)) = AudioCodec::from_caps(caps.caps()) {
+                gst::info!(CAT, "🔊 Detected audio codec: {} in {:?} mode", codec.name(), mode);
+                self.create_audio_track(codec, mode);
+            } else {
+                gst::warning!(CAT, "⚠️ Unsupported audio caps: {}", caps.caps());
+            }
+        }
+        gst::Pad::event_default(pad, Some(&*self.obj()), event)
+    }
+
+    /// Create the audio track for the specified codec and mode.
+    fn create_audio_track(&self, codec: AudioCodec, mode: StreamMode) {
+        let mut state = self.state.lock().unwrap();
+        let track = match mode {
+            StreamMode::Sample => server::AudioTrack::Sample(Arc::new(TrackLocalStaticSample::new(
+                RTCRtpCodecCapability { mime_type: codec.mime_type().to_owned(), clock_rate: 48000, channels: 2, ..Default::default() },
+                "audio".to_owned(),
+                "websink".to_owned(),
+            ))),
+            StreamMode::Rtp => server::AudioTrack::Rtp(Arc::new(TrackLocalStaticRTP::new(
+                RTCRtpCodecCapability { mime_type: codec.mime_type().to_owned(), clock_rate: 48000, channels: 2, ..Default::default() },
+                "audio".to_owned(),
+                "websink".to_owned(),
+            ))),
+        };
+        state.audio_track = Some(track);
+    }
+
     /// Create video track for the specified codec and mode
     fn create_video_track(&self, codec: VideoCodec, mode: StreamMode) -> Result<(), gst::LoggableError> {
         gst::debug!(CAT, "🎥 Creating video track for {} in {:?} mode", codec.name(), mode);
@@ -482,6 +1165,105 @@ impl WebSink {
         Ok(())
     }
 
+    /// Translate a browser input event into a GStreamer navigation event and push
+    /// it upstream through the sink pad. Pointer coordinates arrive normalised in
+    /// [0, 1] and are scaled to the negotiated video resolution.
+    fn push_navigation_event(&self, event: server::InputEvent) {
+        let (width, height) = {
+            let state = self.state.lock().unwrap();
+            (state.video_width, state.video_height)
+        };
+        let px = if width > 0 { event.x * width as f64 } else { event.x };
+        let py = if height > 0 { event.y * height as f64 } else { event.y };
+
+        let structure = match event.kind.as_str() {
+            "mouse-move" => gst::Structure::builder("application/x-gst-navigation")
+                .field("event", "mouse-move")
+                .field("pointer_x", px)
+                .field("pointer_y", py)
+                .build(),
+            "mouse-button-press" | "mouse-button-release" => gst::Structure::builder("application/x-gst-navigation")
+                .field("event", event.kind.as_str())
+                .field("button", event.button)
+                .field("pointer_x", px)
+                .field("pointer_y", py)
+                .build(),
+            "key-press" | "key-release" => {
+                gst::Structure::builder("application/x-gst-navigation").field("event", event.kind.as_str()).field("key", event.key).build()
+            }
+            "mouse-scroll" => gst::Structure::builder("application/x-gst-navigation")
+                .field("event", "mouse-scroll")
+                .field("pointer_x", px)
+                .field("pointer_y", py)
+                .field("delta_pointer_x", event.delta_x)
+                .field("delta_pointer_y", event.delta_y)
+                .build(),
+            "touch-down" | "touch-motion" | "touch-up" => gst::Structure::builder("application/x-gst-navigation")
+                .field("event", event.kind.as_str())
+                .field("identifier", event.id)
+                .field("pointer_x", px)
+                .field("pointer_y", py)
+                .field("pressure", event.pressure)
+                .build(),
+            other => {
+                gst::warning!(CAT, "⚠️ Unknown navigation event type: {}", other);
+                return;
+            }
+        };
+
+        if let Some(sinkpad) = self.obj().static_pad("sink") {
+            if !sinkpad.push_event(gst::event::Navigation::new(structure)) {
+                gst::debug!(CAT, "Navigation event not handled upstream");
+            }
+        }
+    }
+
+    /// Post the current congestion-control target on the element's bus as an
+    /// element message named `websink-bitrate` so an external bwe-request-style
+    /// handler can retune the pipeline without subscribing to the `bitrate` signal.
+    fn post_bitrate_message(&self, bitrate: u32) {
+        let structure = gst::Structure::builder("websink-bitrate").field("bitrate", bitrate).build();
+        let msg = gst::message::Element::builder(structure).src(&*self.obj()).build();
+        let _ = self.obj().post_message(msg);
+    }
+
+    /// Retarget the upstream encoder to the congestion-controlled bitrate by
+    /// walking the sink pad peer chain until an `x264enc`/`x265enc`/`vp8enc`/`vp9enc`
+    /// is found. x264enc and x265enc take `bitrate` in kbit/s; the VPX encoders take
+    /// `target-bitrate` in bits/s.
+    fn apply_target_bitrate(&self, bitrate: u32) {
+        let Some(encoder) = self.find_upstream_encoder() else {
+            gst::debug!(CAT, "No adjustable encoder found upstream; ignoring bitrate estimate {}", bitrate);
+            return;
+        };
+
+        let factory = encoder.factory().map(|f| f.name().to_string()).unwrap_or_default();
+        match factory.as_str() {
+            "x264enc" | "x265enc" => encoder.set_property("bitrate", bitrate / 1000),
+            "vp8enc" | "vp9enc" => encoder.set_property("target-bitrate", bitrate as i32),
+            _ => return,
+        }
+        gst::debug!(CAT, "📉 Retargeted {} to {} bits/s", factory, bitrate);
+    }
+
+    /// Walk upstream from the sink pad looking for a known video encoder.
+    fn find_upstream_encoder(&self) -> Option<gst::Element> {
+        let sinkpad = self.obj().static_pad("sink")?;
+        let mut pad = sinkpad.peer()?;
+        loop {
+            let element = pad.parent_element()?;
+            if let Some(factory) = element.factory() {
+                match factory.name().as_str() {
+                    "x264enc" | "x265enc" | "vp8enc" | "vp9enc" => return Some(element),
+                    _ => {}
+                }
+            }
+            // Continue up through this element's first sink pad.
+            let next_sink = element.sink_pads().into_iter().next()?;
+            pad = next_sink.peer()?;
+        }
+    }
+
     fn start_http_server(
         &self,
         port: u16,
@@ -2,8 +2,13 @@ use gst::glib;
 use gst::prelude::*;
 
 // Modules that contain implementation
+pub mod av1;
+pub mod clocksync;
+pub mod congestion;
+pub mod feedback;
 pub mod imp;
 pub mod server;
+pub mod signaller;
 
 // The WebSink element wrapped in a Rust safe interface
 glib::wrapper! {
@@ -1,6 +1,6 @@
 use axum::{
-    extract::State as AxumState,
-    http::StatusCode,
+    extract::{Path, State as AxumState},
+    http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
@@ -21,6 +21,53 @@ use webrtc::api::interceptor_registry::register_default_interceptors;
 use webrtc::api::media_engine::MediaEngine;
 use webrtc::api::APIBuilder;
 use webrtc::interceptor::registry::Registry;
+use webrtc::rtp_transceiver::rtp_codec::RTPCodecType;
+use webrtc::rtp_transceiver::{RTCPFeedback, TYPE_RTCP_FB_TRANSPORT_CC};
+
+use crate::websink::congestion::{BandwidthEstimator, PacketFeedback};
+use crate::websink::feedback::{SendHistory, SendTimeRecorderBuilder};
+
+/// transport-wide-cc RTP header extension URI negotiated on outgoing tracks.
+pub const TWCC_URI: &str = "http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01";
+/// RFC 6051 64-bit NTP-timestamp RTP header extension for rapid initial A/V sync.
+pub const NTP64_URI: &str = "urn:ietf:params:rtp-hdrext:ntp-64";
+
+/// RFC 7273 clock-signalling description injected into the SDP per media section.
+#[derive(Clone, Debug)]
+pub struct ClockSignalling {
+    pub kind: String,
+    pub ntp_server: String,
+    pub ptp_domain: u32,
+}
+
+impl ClockSignalling {
+    /// Render the `a=ts-refclk:` attribute value describing the reference clock.
+    fn ts_refclk(&self) -> String {
+        match self.kind.as_str() {
+            "ntp" => format!("ts-refclk:ntp={}", self.ntp_server),
+            "ptp" => format!("ts-refclk:ptp=IEEE1588-2008:traceable:{}", self.ptp_domain),
+            _ => "ts-refclk:local".to_string(),
+        }
+    }
+}
+
+/// Insert an RFC 7273 `a=ts-refclk` attribute into each audio/video media section
+/// of an SDP so receivers can reconstruct a common timeline.
+fn inject_clock_attributes(sdp: &str, clock: &ClockSignalling) -> String {
+    let mut out = String::with_capacity(sdp.len() + 128);
+    for line in sdp.split_inclusive("\r\n") {
+        out.push_str(line);
+        if line.starts_with("m=audio") || line.starts_with("m=video") {
+            out.push_str(&format!("a={}\r\n", clock.ts_refclk()));
+            // webrtc-rs randomises each track's starting RTP timestamp and does not
+            // expose it, so we cannot compute a real `a=mediaclk:direct=<offset>`
+            // mapping here. Omit the attribute rather than declare a bogus zero
+            // offset; the per-packet RFC 6051 ntp-64 extension (see `clocksync`)
+            // carries the authoritative RTP-timestamp-to-wallclock mapping instead.
+        }
+    }
+    out
+}
 use webrtc::peer_connection::configuration::RTCConfiguration;
 use webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState;
 use webrtc::peer_connection::sdp::session_description::RTCSessionDescription;
@@ -46,11 +93,37 @@ pub struct SessionRequest {
     pub offer: serde_json::Value,
 }
 
+// Input event delivered over the navigation data channel from the browser. A
+// single JSON shape carries all event kinds; unused fields default to zero.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InputEvent {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub x: f64,
+    #[serde(default)]
+    pub y: f64,
+    #[serde(default)]
+    pub button: i32,
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub delta_x: f64,
+    #[serde(default)]
+    pub delta_y: f64,
+    // Touch identifier and pressure for multi-touch events.
+    #[serde(default)]
+    pub id: u32,
+    #[serde(default)]
+    pub pressure: f64,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SessionResponse {
     pub answer: serde_json::Value,
     pub session_id: String,
     pub negotiated_codec: Option<String>,
+    pub negotiated_audio_codec: Option<String>,
 }
 // Video track enum to support both Sample and RTP modes
 #[derive(Clone)]
@@ -73,6 +146,34 @@ impl VideoTrack {
             VideoTrack::Rtp(track) => track.codec().clone().mime_type,
         }
     }
+
+    /// True when this track forwards pre-packetized RTP rather than samples.
+    pub fn is_rtp(&self) -> bool {
+        matches!(self, VideoTrack::Rtp(_))
+    }
+}
+
+// Audio track enum, parallel to VideoTrack, for Opus in Sample or RTP mode.
+#[derive(Clone)]
+pub enum AudioTrack {
+    Sample(Arc<TrackLocalStaticSample>),
+    Rtp(Arc<TrackLocalStaticRTP>),
+}
+
+impl AudioTrack {
+    pub fn as_track_local(&self) -> Arc<dyn TrackLocal + Send + Sync> {
+        match self {
+            AudioTrack::Sample(track) => Arc::clone(track) as Arc<dyn TrackLocal + Send + Sync>,
+            AudioTrack::Rtp(track) => Arc::clone(track) as Arc<dyn TrackLocal + Send + Sync>,
+        }
+    }
+
+    pub fn codec_mime_type(&self) -> String {
+        match self {
+            AudioTrack::Sample(track) => track.codec().clone().mime_type,
+            AudioTrack::Rtp(track) => track.codec().clone().mime_type,
+        }
+    }
 }
 
 // Element state containing HTTP server and WebRTC components
@@ -85,17 +186,68 @@ pub struct State {
     pub unblock_rx: Option<mpsc::Receiver<i32>>,
     // WebRTC components
     pub video_track: Option<VideoTrack>,
+    // Optional audio track added via the request audio sink pad, muxed into the
+    // same peer connection so browsers receive synchronized A/V.
+    pub audio_track: Option<AudioTrack>,
     pub webrtc_config: Option<RTCConfiguration>,
+    // Congestion control: per-peer target bitrates and the aggregate channel the
+    // element drains to retarget the upstream encoder. The aggregate is the min
+    // across peers since they all share one encoder.
+    pub peer_bitrates: HashMap<String, u32>,
+    pub bitrate_tx: Option<mpsc::Sender<u32>>,
+    pub bitrate_rx: Option<mpsc::Receiver<u32>>,
+    pub min_bitrate: u32,
+    pub max_bitrate: u32,
+    // Navigation: input events received from browsers are forwarded here for the
+    // element to drain and push upstream as GStreamer navigation events. The
+    // negotiated video resolution normalises pointer coordinates.
+    pub enable_navigation: bool,
+    pub video_width: u32,
+    pub video_height: u32,
+    pub nav_tx: Option<mpsc::Sender<InputEvent>>,
+    pub nav_rx: Option<mpsc::Receiver<InputEvent>>,
+    // TLS: when set, the signaling page and socket are served over HTTPS/WSS.
+    pub tls: Option<TlsConfig>,
+    // RFC 7273/6051 clock signalling, when enabled on the element.
+    pub clock_signalling: Option<ClockSignalling>,
+    // ICE candidate gathering restrictions applied via the SettingEngine.
+    pub ice_network_types: Vec<String>,
+    pub host_ip: Option<String>,
+    // Signalling transport selected by the element's `signaller` property.
+    pub signaller: crate::websink::signaller::SignallerKind,
+    // Per-session sinks for server-initiated renegotiation offers. Populated by
+    // signalling backends that can push to the client (e.g. the WebSocket
+    // backend); a mid-stream codec/mode change drives a fresh offer through these.
+    pub renegotiation_txs: HashMap<String, mpsc::UnboundedSender<String>>,
 }
 
-// Handle WebRTC session request (create peer connection and answer)
-pub async fn handle_session_request(
-    req: SessionRequest,
-    state: Arc<Mutex<State>>,
-) -> Result<SessionResponse, Box<dyn std::error::Error + Send + Sync>> {
-    gst::info!(CAT, "🎯 Processing WebRTC session request");
+/// Paths to the PEM certificate chain and private key used for HTTPS/WSS.
+///
+/// There is intentionally no companion "insecure" mode: this server only
+/// terminates *inbound* HTTPS/WSS for the signaling page and socket, and the
+/// WebRTC media path authenticates peers via the SDP DTLS fingerprint, not a
+/// certificate chain. An `insecure-tls` switch would have had nothing to
+/// relax on either path, so it was removed rather than wired up as a no-op.
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    pub certificate_file: String,
+    pub private_key_file: String,
+}
+
+/// A freshly built peer connection with its tracks attached, ready for SDP
+/// exchange. Shared by every signalling backend.
+pub struct PreparedPeerConnection {
+    pub peer_connection: Arc<webrtc::peer_connection::RTCPeerConnection>,
+    pub rtp_sender: Arc<webrtc::rtp_transceiver::rtp_sender::RTCRtpSender>,
+    pub video_codec: String,
+    pub audio_codec: Option<String>,
+    pub send_history: SendHistory,
+}
 
-    // Get the shared video track and config from state
+/// Build a peer connection from the shared state: configure the MediaEngine
+/// (TWCC, optional ntp-64), apply ICE settings, attach the video and optional
+/// audio tracks, and wire the navigation data channel. Does not touch SDP.
+pub async fn build_peer_connection(state: Arc<Mutex<State>>) -> Result<PreparedPeerConnection, Box<dyn std::error::Error + Send + Sync>> {
     let (webrtc_config, video_track) = {
         let state_guard = state.lock().unwrap();
         let config = state_guard.webrtc_config.clone().ok_or("WebRTC config not initialized")?;
@@ -111,18 +263,261 @@ pub async fn handle_session_request(
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
 
+    // Negotiate the transport-wide-cc header extension on the outgoing video so
+    // the receiver sends TWCC feedback we can drive the estimator with.
+    m.register_feedback(RTCPFeedback { typ: TYPE_RTCP_FB_TRANSPORT_CC.to_owned(), parameter: String::new() }, RTPCodecType::Video);
+    m.register_header_extension(
+        webrtc::rtp_transceiver::rtp_codec::RTCRtpHeaderExtensionCapability { uri: TWCC_URI.to_owned() },
+        RTPCodecType::Video,
+        None,
+    )?;
+
+    // Offer the RFC 6051 ntp-64 header extension for rapid initial sync when clock
+    // signalling is enabled.
+    let clock_signalling = { state.lock().unwrap().clock_signalling.clone() };
+    if clock_signalling.is_some() {
+        m.register_header_extension(
+            webrtc::rtp_transceiver::rtp_codec::RTCRtpHeaderExtensionCapability { uri: NTP64_URI.to_owned() },
+            RTPCodecType::Video,
+            None,
+        )?;
+        m.register_header_extension(
+            webrtc::rtp_transceiver::rtp_codec::RTCRtpHeaderExtensionCapability { uri: NTP64_URI.to_owned() },
+            RTPCodecType::Audio,
+            None,
+        )?;
+    }
+
     let mut registry = Registry::new();
     registry = register_default_interceptors(registry, &mut m)?;
 
-    let api = APIBuilder::new().with_media_engine(m).with_interceptor_registry(registry).build();
+    // Record real send times keyed by TWCC sequence number so the congestion
+    // estimator can correlate arrival feedback against the actual send schedule.
+    let send_history: SendHistory = Arc::new(Mutex::new(std::collections::BTreeMap::new()));
+    registry.add(Box::new(SendTimeRecorderBuilder::new(Arc::clone(&send_history))));
+
+    // Stamp outgoing packets with the RFC 6051 ntp-64 extension for rapid A/V sync
+    // when clock signalling is enabled and the extension was negotiated above.
+    if clock_signalling.is_some() {
+        registry.add(Box::new(crate::websink::clocksync::NtpStampBuilder));
+    }
+
+    // Restrict candidate gathering and pin the host candidate IP when configured,
+    // e.g. IPv4-only UDP on embedded boards or a fixed interface address.
+    let (network_types, host_ip) = {
+        let guard = state.lock().unwrap();
+        (guard.ice_network_types.clone(), guard.host_ip.clone())
+    };
+    let mut setting_engine = webrtc::api::setting_engine::SettingEngine::default();
+    if !network_types.is_empty() {
+        let types = network_types.iter().filter_map(|t| parse_network_type(t)).collect::<Vec<_>>();
+        if !types.is_empty() {
+            setting_engine.set_network_types(types);
+        }
+    }
+    if let Some(ip) = host_ip {
+        setting_engine.set_nat_1to1_ips(vec![ip], webrtc::ice_transport::ice_candidate_type::RTCIceCandidateType::Host);
+    }
+
+    let api = APIBuilder::new().with_media_engine(m).with_interceptor_registry(registry).with_setting_engine(setting_engine).build();
 
     // Create a new peer connection using the API and shared config
     let peer_connection = Arc::new(api.new_peer_connection(webrtc_config).await?);
     gst::info!(CAT, "📞 Created new peer connection");
 
-    let _rtp_sender = peer_connection.add_track(video_track.as_track_local()).await?;
+    let rtp_sender = peer_connection.add_track(video_track.as_track_local()).await?;
     gst::info!(CAT, "🎥 Added video track to peer connection");
 
+    // Add the audio track to the same peer connection when one is present so the
+    // browser negotiates synchronized audio and video in a single offer.
+    let audio_track = {
+        let guard = state.lock().unwrap();
+        guard.audio_track.clone()
+    };
+    let negotiated_audio_codec = if let Some(audio_track) = &audio_track {
+        peer_connection.add_track(audio_track.as_track_local()).await?;
+        let codec = audio_track.codec_mime_type().to_lowercase();
+        gst::info!(CAT, "🔊 Added audio track to peer connection ({})", codec);
+        Some(codec)
+    } else {
+        None
+    };
+
+    // Forward browser input events over the data channel up the pipeline as
+    // navigation events, when enabled.
+    let (nav_enabled, nav_tx) = {
+        let guard = state.lock().unwrap();
+        (guard.enable_navigation, guard.nav_tx.clone())
+    };
+    if nav_enabled {
+        if let Some(nav_tx) = nav_tx {
+            peer_connection.on_data_channel(Box::new(move |dc| {
+                let nav_tx = nav_tx.clone();
+                Box::pin(async move {
+                    // Only the input channel carries navigation events; leave any
+                    // other data channels (e.g. application-defined ones) alone.
+                    let label = dc.label();
+                    if label != "input" && label != "navigation" {
+                        gst::debug!(CAT, "Ignoring non-navigation data channel: {}", label);
+                        return;
+                    }
+                    gst::info!(CAT, "🎮 Navigation data channel opened: {}", label);
+                    dc.on_message(Box::new(move |msg| {
+                        let nav_tx = nav_tx.clone();
+                        Box::pin(async move {
+                            match serde_json::from_slice::<InputEvent>(&msg.data) {
+                                Ok(event) => {
+                                    let _ = nav_tx.try_send(event);
+                                }
+                                Err(e) => gst::warning!(CAT, "⚠️ Ignoring malformed input event: {}", e),
+                            }
+                        })
+                    }));
+                })
+            }));
+        }
+    }
+
+    Ok(PreparedPeerConnection {
+        peer_connection,
+        rtp_sender,
+        video_codec: actual_codec,
+        audio_codec: negotiated_audio_codec,
+        send_history,
+    })
+}
+
+/// Register a freshly negotiated peer connection in the shared state: bump the
+/// peer count, start congestion control, and install the disconnect handler.
+/// Returns the generated session id.
+pub fn register_peer_connection(
+    state: &Arc<Mutex<State>>,
+    peer_connection: Arc<webrtc::peer_connection::RTCPeerConnection>,
+    rtp_sender: Arc<webrtc::rtp_transceiver::rtp_sender::RTCRtpSender>,
+    send_history: SendHistory,
+) -> String {
+    let session_id = Uuid::new_v4().to_string();
+
+    {
+        let mut state_guard = state.lock().unwrap();
+        state_guard.peer_connections.insert(session_id.clone(), Arc::clone(&peer_connection));
+        let count = state_guard.peer_connections.len() as i32;
+        if let Some(tx) = &state_guard.unblock_tx {
+            let _ = tx.try_send(count);
+        }
+        gst::info!(CAT, "👥 Added new peer connection, total count: {}", count);
+    }
+
+    spawn_congestion_control(rtp_sender, send_history, Arc::clone(state), session_id.clone());
+
+    let state_clone = Arc::clone(state);
+    let session_id_clone = session_id.clone();
+    peer_connection.on_peer_connection_state_change(Box::new(move |s| {
+        gst::debug!(CAT, "🔄 Peer connection state changed to: {:?} for session {}", s, session_id_clone);
+        let mut state_guard = state_clone.lock().unwrap();
+        match s {
+            RTCPeerConnectionState::Disconnected | RTCPeerConnectionState::Failed | RTCPeerConnectionState::Closed => {
+                gst::info!(CAT, "🔌 Peer disconnected, removing session: {}", session_id_clone);
+                state_guard.peer_connections.remove(&session_id_clone);
+                state_guard.peer_bitrates.remove(&session_id_clone);
+                state_guard.renegotiation_txs.remove(&session_id_clone);
+                if let Some(tx) = &state_guard.unblock_tx {
+                    let _ = tx.try_send(state_guard.peer_connections.len() as i32);
+                }
+                gst::info!(CAT, "📊 Updated peer count to: {}", state_guard.peer_connections.len() as i32);
+            }
+            RTCPeerConnectionState::Connected => {
+                gst::debug!(CAT, "🕼 Peer connected successfully: {}, num peers: {}", session_id_clone, state_guard.peer_connections.len());
+            }
+            _ => {
+                gst::debug!(CAT, "🔄 Peer connection state: {:?}", s);
+            }
+        }
+        Box::pin(async {})
+    }));
+
+    session_id
+}
+
+/// Swap the current `video_track` onto every connected peer's sender and drive an
+/// SDP renegotiation. Called when `set_caps` produces a new track because the
+/// codec or stream mode changed mid-session — without this, already-connected
+/// viewers keep writing to the orphaned old track and freeze.
+///
+/// Peers whose signalling backend registered a renegotiation sink (see
+/// `State::renegotiation_txs`) receive a fresh offer; peers without one (the
+/// stateless HTTP POST flow has no back-channel) still get the track replaced in
+/// place, which is sufficient whenever the new codec is payload-compatible.
+pub fn renegotiate_video_track(state: &Arc<Mutex<State>>) {
+    let (runtime_ok, track, peers, txs) = {
+        let guard = state.lock().unwrap();
+        let track = guard.video_track.as_ref().map(|t| t.as_track_local());
+        let peers: Vec<(String, Arc<webrtc::peer_connection::RTCPeerConnection>)> =
+            guard.peer_connections.iter().map(|(id, pc)| (id.clone(), Arc::clone(pc))).collect();
+        let txs = guard.renegotiation_txs.clone();
+        (guard.runtime.is_some(), track, peers, txs)
+    };
+
+    let (Some(track), true) = (track, runtime_ok) else { return };
+    if peers.is_empty() {
+        return;
+    }
+
+    let state = Arc::clone(state);
+    let rt_handle = {
+        let guard = state.lock().unwrap();
+        guard.runtime.as_ref().map(|rt| rt.handle().clone())
+    };
+    let Some(handle) = rt_handle else { return };
+
+    handle.spawn(async move {
+        for (session_id, pc) in peers {
+            // Replace the media on the first video sender of this peer.
+            for sender in pc.get_senders().await {
+                if let Some(current) = sender.track().await {
+                    if current.kind() == track.kind() {
+                        if let Err(e) = sender.replace_track(Some(Arc::clone(&track))).await {
+                            gst::warning!(CAT, "Failed to replace track for session {}: {}", session_id, e);
+                        }
+                        break;
+                    }
+                }
+            }
+
+            // Drive renegotiation where the client can receive a pushed offer.
+            if let Some(tx) = txs.get(&session_id) {
+                match pc.create_offer(None).await {
+                    Ok(offer) => {
+                        if let Err(e) = pc.set_local_description(offer.clone()).await {
+                            gst::warning!(CAT, "Failed to set local description for session {}: {}", session_id, e);
+                            continue;
+                        }
+                        if tx.send(offer.sdp).is_err() {
+                            gst::debug!(CAT, "Renegotiation sink closed for session {}", session_id);
+                        }
+                    }
+                    Err(e) => gst::warning!(CAT, "Failed to create renegotiation offer for session {}: {}", session_id, e),
+                }
+            }
+        }
+    });
+}
+
+// Handle WebRTC session request (create peer connection and answer)
+pub async fn handle_session_request(
+    req: SessionRequest,
+    state: Arc<Mutex<State>>,
+) -> Result<SessionResponse, Box<dyn std::error::Error + Send + Sync>> {
+    gst::info!(CAT, "🎯 Processing WebRTC session request");
+
+    let clock_signalling = { state.lock().unwrap().clock_signalling.clone() };
+    let pc = build_peer_connection(Arc::clone(&state)).await?;
+    let peer_connection = pc.peer_connection;
+    let rtp_sender = pc.rtp_sender;
+    let actual_codec = pc.video_codec;
+    let negotiated_audio_codec = pc.audio_codec;
+    let send_history = pc.send_history;
+
     // Parse the offer from the request
     let offer: RTCSessionDescription = serde_json::from_value(req.offer)?;
     gst::info!(CAT, "📨 Parsed offer from client");
@@ -157,58 +552,147 @@ pub async fn handle_session_request(
     gst::info!(CAT, "🧊 ICE gathering completed");
 
     // Get the final answer with ICE candidates
-    let final_answer = peer_connection.local_description().await.ok_or("Failed to get local description")?;
+    let mut final_answer = peer_connection.local_description().await.ok_or("Failed to get local description")?;
 
-    // Generate session ID
-    let session_id = Uuid::new_v4().to_string();
+    // Inject RFC 7273 clock attributes into the answer when signalling is enabled.
+    if let Some(clock) = &clock_signalling {
+        let sdp = inject_clock_attributes(&final_answer.sdp, clock);
+        final_answer = RTCSessionDescription::answer(sdp)?;
+        gst::info!(CAT, "⏰ Injected RFC 7273 clock attributes into answer");
+    }
 
-    // Store the peer connection in the state and update peer count
-    {
-        let mut state_guard = state.lock().unwrap();
-        state_guard.peer_connections.insert(session_id.clone(), Arc::clone(&peer_connection));
+    // Register the connection, start congestion control, wire disconnect cleanup.
+    let session_id = register_peer_connection(&state, Arc::clone(&peer_connection), rtp_sender, send_history);
 
-        // Update peer count and send notification
-        let count = state_guard.peer_connections.len() as i32;
-        if let Some(tx) = &state_guard.unblock_tx {
-            let _ = tx.try_send(count);
+    // Serialize answer to JSON
+    let answer_json = serde_json::to_value(&final_answer)?;
+
+    let response = SessionResponse {
+        answer: answer_json,
+        session_id: session_id.clone(),
+        negotiated_codec: Some(actual_codec.clone()),
+        negotiated_audio_codec,
+    };
+
+    gst::info!(CAT, "✅ WebRTC session established with ID: {} using codec: {}", session_id, actual_codec);
+    Ok(response)
+}
+
+/// Read TWCC RTCP feedback off `rtp_sender`, run the GCC estimator, and publish
+/// the aggregate target bitrate (min across connected peers) on `bitrate_tx`.
+fn spawn_congestion_control(
+    rtp_sender: Arc<webrtc::rtp_transceiver::rtp_sender::RTCRtpSender>,
+    send_history: SendHistory,
+    state: Arc<Mutex<State>>,
+    session_id: String,
+) {
+    use webrtc::rtcp::transport_feedbacks::transport_layer_cc::TransportLayerCc;
+
+    let (min_bitrate, max_bitrate, bitrate_tx) = {
+        let guard = state.lock().unwrap();
+        (guard.min_bitrate, guard.max_bitrate, guard.bitrate_tx.clone())
+    };
+    let Some(bitrate_tx) = bitrate_tx else { return };
+
+    tokio::spawn(async move {
+        let mut estimator = BandwidthEstimator::new(min_bitrate, max_bitrate);
+        let mut buf = vec![0u8; 1500];
+        loop {
+            let (packets, _attrs) = match rtp_sender.read(&mut buf).await {
+                Ok(r) => r,
+                Err(_) => break,
+            };
+            for pkt in packets {
+                let Some(twcc) = pkt.as_any().downcast_ref::<TransportLayerCc>() else { continue };
+                let (feedback, lost) = decode_twcc(twcc, &send_history);
+                if feedback.is_empty() && lost == 0 {
+                    continue;
+                }
+                let target = estimator.process_feedback(&feedback, lost);
+
+                let aggregate = {
+                    let mut guard = state.lock().unwrap();
+                    guard.peer_bitrates.insert(session_id.clone(), target);
+                    guard.peer_bitrates.values().copied().min()
+                };
+                if let Some(aggregate) = aggregate {
+                    let _ = bitrate_tx.try_send(aggregate);
+                }
+            }
         }
-        gst::info!(CAT, "👥 Added new peer connection, total count: {}", count);
-    }
+        let mut guard = state.lock().unwrap();
+        guard.peer_bitrates.remove(&session_id);
+    });
+}
 
-    // Handle peer disconnection
-    let state_clone = Arc::clone(&state);
-    let session_id_clone = session_id.clone();
-    peer_connection.on_peer_connection_state_change(Box::new(move |s| {
-        gst::debug!(CAT, "🔄 Peer connection state changed to: {:?} for session {}", s, session_id_clone);
-        let mut state_guard = state_clone.lock().unwrap();
-        match s {
-            RTCPeerConnectionState::Disconnected | RTCPeerConnectionState::Failed | RTCPeerConnectionState::Closed => {
-                gst::info!(CAT, "🔌 Peer disconnected, removing session: {}", session_id_clone);
-                state_guard.peer_connections.remove(&session_id_clone);
-                // Update peer count and send notification
-                if let Some(tx) = &state_guard.unblock_tx {
-                    let _ = tx.try_send(state_guard.peer_connections.len() as i32);
+/// Turn a TWCC feedback packet into per-packet (send, arrival) pairs plus a lost
+/// count. Arrival times come from the reference time and recv deltas; send times
+/// are looked up in `send_history` by the packet's transport sequence number, so
+/// the estimator sees the real send schedule rather than a synthesised cadence.
+fn decode_twcc(
+    twcc: &webrtc::rtcp::transport_feedbacks::transport_layer_cc::TransportLayerCc,
+    send_history: &SendHistory,
+) -> (Vec<PacketFeedback>, u32) {
+    use webrtc::rtcp::transport_feedbacks::transport_layer_cc::SymbolTypeTcc;
+
+    // reference_time is in 64ms units; webrtc-rs already scales recv deltas to
+    // microseconds when unmarshalling, so they are used as-is below.
+    let mut arrival_us = (twcc.reference_time as i64) * 64_000;
+    let mut feedback = Vec::new();
+    let mut lost = 0u32;
+    let mut delta_idx = 0;
+    let mut seq = twcc.base_sequence_number;
+
+    let mut status = Vec::new();
+    for chunk in &twcc.packet_chunks {
+        match chunk {
+            webrtc::rtcp::transport_feedbacks::transport_layer_cc::PacketStatusChunk::RunLengthChunk(c) => {
+                for _ in 0..c.run_length {
+                    status.push(c.packet_status_symbol);
                 }
-                gst::info!(CAT, "📊 Updated peer count to: {}", state_guard.peer_connections.len() as i32);
             }
-            RTCPeerConnectionState::Connected => {
-                gst::debug!(CAT, "🕼 Peer connected successfully: {}, num peers: {}", session_id_clone, state_guard.peer_connections.len());
+            webrtc::rtcp::transport_feedbacks::transport_layer_cc::PacketStatusChunk::StatusVectorChunk(c) => {
+                status.extend_from_slice(&c.symbol_list);
+            }
+        }
+    }
+
+    let mut history = send_history.lock().unwrap();
+    for symbol in status {
+        let this_seq = seq;
+        seq = seq.wrapping_add(1);
+        match symbol {
+            SymbolTypeTcc::PacketNotReceived => {
+                lost += 1;
+                history.remove(&this_seq);
             }
             _ => {
-                gst::debug!(CAT, "🔄 Peer connection state: {:?}", s);
+                if let Some(delta) = twcc.recv_deltas.get(delta_idx) {
+                    delta_idx += 1;
+                    arrival_us += delta.delta;
+                    // Correlate against the recorded send time for this transport
+                    // sequence number; drop the feedback if we never saw the send.
+                    if let Some(send_time_us) = history.remove(&this_seq) {
+                        feedback.push(PacketFeedback { send_time_us, arrival_time_us: arrival_us });
+                    }
+                }
             }
         }
+    }
 
-        Box::pin(async {})
-    }));
-
-    // Serialize answer to JSON
-    let answer_json = serde_json::to_value(&final_answer)?;
-
-    let response = SessionResponse { answer: answer_json, session_id: session_id.clone(), negotiated_codec: Some(actual_codec.clone()) };
+    (feedback, lost)
+}
 
-    gst::info!(CAT, "✅ WebRTC session established with ID: {} using codec: {}", session_id, actual_codec);
-    Ok(response)
+/// Map a network-type token (`udp4`, `udp6`, `tcp4`, `tcp6`) to its ICE enum.
+fn parse_network_type(token: &str) -> Option<webrtc::ice::network_type::NetworkType> {
+    use webrtc::ice::network_type::NetworkType;
+    match token {
+        "udp4" => Some(NetworkType::Udp4),
+        "udp6" => Some(NetworkType::Udp6),
+        "tcp4" => Some(NetworkType::Tcp4),
+        "tcp6" => Some(NetworkType::Tcp6),
+        _ => None,
+    }
 }
 
 fn next_free_port(mut port: u16) -> u16 {
@@ -220,7 +704,7 @@ fn next_free_port(mut port: u16) -> u16 {
     }
 }
 
-async fn handle_session(
+pub async fn handle_session(
     AxumState(state): AxumState<Arc<Mutex<State>>>,
     Json(req): Json<SessionRequest>,
 ) -> Result<Json<SessionResponse>, AppError> {
@@ -230,6 +714,58 @@ async fn handle_session(
     Ok(Json(response))
 }
 
+// WHEP (WebRTC-HTTP Egress Protocol) endpoint: a raw SDP offer in, a raw SDP answer
+// out, with a resource URL for teardown. Lets standards-based players (OBS,
+// GStreamer whepsrc, browser WHEP clients) consume the stream without custom JS.
+async fn handle_whep(AxumState(state): AxumState<Arc<Mutex<State>>>, headers: HeaderMap, body: String) -> Result<Response, AppError> {
+    gst::info!(CAT, "Received WHEP offer");
+
+    // WHEP offers are carried as application/sdp; reject any other declared type so
+    // clients get a clear 415 rather than a confusing SDP parse error. A request
+    // with no Content-Type header is accepted and parsed as SDP.
+    let content_type = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).unwrap_or("");
+    if !content_type.is_empty() && !content_type.starts_with("application/sdp") {
+        return Ok((StatusCode::UNSUPPORTED_MEDIA_TYPE, "expected Content-Type: application/sdp").into_response());
+    }
+
+    let offer = RTCSessionDescription::offer(body)?;
+    let req = SessionRequest { offer: serde_json::to_value(offer)? };
+    let response = handle_session_request(req, state).await?;
+
+    // The answer is serialized as an RTCSessionDescription; WHEP wants the bare SDP.
+    let answer_sdp = response.answer.get("sdp").and_then(|v| v.as_str()).ok_or("answer missing sdp")?.to_string();
+
+    let location = format!("/whep/{}", response.session_id);
+    Ok((StatusCode::CREATED, [(header::CONTENT_TYPE, "application/sdp"), (header::LOCATION, location.as_str())], answer_sdp)
+        .into_response())
+}
+
+// WHEP resource teardown: close the peer connection and drop it, mirroring the
+// disconnect cleanup in on_peer_connection_state_change.
+async fn handle_whep_delete(AxumState(state): AxumState<Arc<Mutex<State>>>, Path(session_id): Path<String>) -> Response {
+    let peer_connection = {
+        let mut guard = state.lock().unwrap();
+        let pc = guard.peer_connections.remove(&session_id);
+        guard.peer_bitrates.remove(&session_id);
+        guard.renegotiation_txs.remove(&session_id);
+        if let Some(tx) = &guard.unblock_tx {
+            let _ = tx.try_send(guard.peer_connections.len() as i32);
+        }
+        pc
+    };
+
+    match peer_connection {
+        Some(pc) => {
+            if let Err(e) = pc.close().await {
+                gst::warning!(CAT, "Error closing WHEP peer connection {}: {}", session_id, e);
+            }
+            gst::info!(CAT, "🔌 WHEP session {} torn down", session_id);
+            StatusCode::OK.into_response()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
 async fn serve_static(uri: axum::http::Uri) -> impl IntoResponse {
     let path = uri.path().trim_start_matches('/');
     let path_to_serve = if path.is_empty() { "index.html" } else { path };
@@ -251,7 +787,7 @@ async fn serve_static(uri: axum::http::Uri) -> impl IntoResponse {
     }
 }
 
-struct AppError(Box<dyn std::error::Error + Send + Sync>);
+pub struct AppError(Box<dyn std::error::Error + Send + Sync>);
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
@@ -303,22 +839,50 @@ pub fn start_http_server(
         reset = RESET
     );
 
-    let app = Router::new().route("/api/session", post(handle_session)).fallback(get(serve_static)).with_state(state);
+    let (tls, signaller) = {
+        let guard = state.lock().unwrap();
+        (guard.tls.clone(), guard.signaller)
+    };
+
+    // Mount the selected signalling backend plus the transport-agnostic WHEP egress.
+    let mut router = Router::new();
+    router = signaller.mount(router);
+    let app = router
+        .route("/whep", post(handle_whep))
+        .route("/whep/{session_id}", axum::routing::delete(handle_whep_delete))
+        .fallback(get(serve_static))
+        .with_state(state);
 
-    let addr = format!("[::]:{}", port);
+    let addr: std::net::SocketAddr = format!("[::]:{}", port).parse()?;
 
     let handle = rt.spawn(async move {
-        let listener = match tokio::net::TcpListener::bind(&addr).await {
-            Ok(l) => l,
-            Err(e) => {
-                gst::error!(CAT, "Failed to bind to {}: {}", addr, e);
-                return;
+        match tls {
+            Some(tls) => {
+                let config = match axum_server::tls_rustls::RustlsConfig::from_pem_file(&tls.certificate_file, &tls.private_key_file).await {
+                    Ok(c) => c,
+                    Err(e) => {
+                        gst::error!(CAT, "Failed to load TLS material: {}", e);
+                        return;
+                    }
+                };
+                gst::info!(CAT, "Starting HTTPS server on {}", addr);
+                if let Err(e) = axum_server::bind_rustls(addr, config).serve(app.into_make_service()).await {
+                    gst::error!(CAT, "HTTPS server error: {}", e);
+                }
+            }
+            None => {
+                let listener = match tokio::net::TcpListener::bind(&addr).await {
+                    Ok(l) => l,
+                    Err(e) => {
+                        gst::error!(CAT, "Failed to bind to {}: {}", addr, e);
+                        return;
+                    }
+                };
+                gst::info!(CAT, "Starting HTTP server on {}", addr);
+                if let Err(e) = axum::serve(listener, app).await {
+                    gst::error!(CAT, "HTTP server error: {}", e);
+                }
             }
-        };
-
-        gst::info!(CAT, "Starting HTTP server on {}", addr);
-        if let Err(e) = axum::serve(listener, app).await {
-            gst::error!(CAT, "HTTP server error: {}", e);
         }
         gst::info!(CAT, "HTTP server stopped");
     });
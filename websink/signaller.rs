@@ -0,0 +1,204 @@
+// Pluggable signalling backends.
+//
+// The peer-connection lifecycle (build the connection, exchange SDP, register it
+// in the shared state) is identical no matter how signalling bytes reach the
+// client. This module abstracts the transport behind a `Signaller` trait with two
+// implementations: the original HTTP POST offer/answer flow (`HttpSignaller`) and
+// a long-lived WebSocket backend (`WebSocketSignaller`) that additionally supports
+// trickle ICE. The element selects a backend through its `signaller` property.
+
+use std::sync::{Arc, Mutex};
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State as AxumState;
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use webrtc::ice_transport::ice_candidate::RTCIceCandidateInit;
+use webrtc::peer_connection::sdp::session_description::RTCSessionDescription;
+
+use crate::websink::imp::CAT;
+use crate::websink::server::{build_peer_connection, handle_session, register_peer_connection, State};
+
+/// Transport used to exchange SDP and ICE with clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignallerKind {
+    #[default]
+    Http,
+    WebSocket,
+}
+
+impl SignallerKind {
+    /// Parse the `signaller` element property, falling back to HTTP for any
+    /// unrecognised value.
+    pub fn from_property_str(s: &str) -> Self {
+        match s {
+            "websocket" | "ws" => SignallerKind::WebSocket,
+            _ => SignallerKind::Http,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignallerKind::Http => "http",
+            SignallerKind::WebSocket => "websocket",
+        }
+    }
+
+    /// Mount this backend's routes onto the signalling router.
+    pub fn mount(&self, router: Router<Arc<Mutex<State>>>) -> Router<Arc<Mutex<State>>> {
+        match self {
+            SignallerKind::Http => router.route("/api/session", post(handle_session)),
+            SignallerKind::WebSocket => router.route("/ws", get(ws_handler)),
+        }
+    }
+}
+
+// Wire format for the WebSocket backend. A single tagged message carries offers,
+// answers, trickled ICE candidates, and teardown.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum SignalMessage {
+    Offer { sdp: String },
+    Answer { sdp: String },
+    Ice { candidate: String, #[serde(rename = "sdpMid")] sdp_mid: Option<String>, #[serde(rename = "sdpMLineIndex")] sdp_mline_index: Option<u16> },
+    Bye,
+}
+
+async fn ws_handler(AxumState(state): AxumState<Arc<Mutex<State>>>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| handle_socket(socket, state))
+}
+
+/// Drive one session over a WebSocket with trickle ICE: candidates are streamed
+/// to the client as they are gathered rather than blocking on gathering-complete.
+async fn handle_socket(mut socket: WebSocket, state: Arc<Mutex<State>>) {
+    gst::info!(CAT, "🔌 WebSocket signalling connection opened");
+
+    let prepared = match build_peer_connection(Arc::clone(&state)).await {
+        Ok(p) => p,
+        Err(e) => {
+            gst::error!(CAT, "Failed to build peer connection: {}", e);
+            return;
+        }
+    };
+    let peer_connection = prepared.peer_connection;
+
+    // Stream locally gathered ICE candidates back to the client.
+    let (cand_tx, mut cand_rx) = mpsc::channel::<String>(16);
+    peer_connection.on_ice_candidate(Box::new(move |candidate| {
+        let cand_tx = cand_tx.clone();
+        Box::pin(async move {
+            if let Some(candidate) = candidate {
+                if let Ok(init) = candidate.to_json() {
+                    if let Ok(json) = serde_json::to_string(&SignalMessage::Ice {
+                        candidate: init.candidate,
+                        sdp_mid: init.sdp_mid,
+                        sdp_mline_index: init.sdp_mline_index,
+                    }) {
+                        let _ = cand_tx.send(json).await;
+                    }
+                }
+            }
+        })
+    }));
+
+    let mut session_id: Option<String> = None;
+    let mut rtp_sender = Some((prepared.rtp_sender, prepared.send_history));
+    // Receives server-initiated renegotiation offers (e.g. after a mid-stream
+    // codec change); registered in the shared state once the session exists.
+    let (renego_tx, mut renego_rx) = mpsc::unbounded_channel::<String>();
+
+    loop {
+        tokio::select! {
+            // Outgoing trickled candidates.
+            Some(json) = cand_rx.recv() => {
+                if socket.send(Message::Text(json.into())).await.is_err() {
+                    break;
+                }
+            }
+            // Server-initiated renegotiation offers.
+            Some(sdp) = renego_rx.recv() => {
+                if let Ok(json) = serde_json::to_string(&SignalMessage::Offer { sdp }) {
+                    if socket.send(Message::Text(json.into())).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            // Incoming client messages.
+            msg = socket.recv() => {
+                let Some(Ok(msg)) = msg else { break };
+                let text = match msg {
+                    Message::Text(t) => t.to_string(),
+                    Message::Close(_) => break,
+                    _ => continue,
+                };
+                let Ok(parsed) = serde_json::from_str::<SignalMessage>(&text) else {
+                    gst::warning!(CAT, "Ignoring malformed signalling message");
+                    continue;
+                };
+                match parsed {
+                    SignalMessage::Offer { sdp } => {
+                        if let Err(e) = on_offer(&mut socket, &peer_connection, sdp).await {
+                            gst::error!(CAT, "Failed to answer offer: {}", e);
+                            break;
+                        }
+                        if let Some((rtp_sender, send_history)) = rtp_sender.take() {
+                            let sid = register_peer_connection(&state, Arc::clone(&peer_connection), rtp_sender, send_history);
+                            // Register the renegotiation sink so mid-stream caps
+                            // changes can push a fresh offer to this client.
+                            state.lock().unwrap().renegotiation_txs.insert(sid.clone(), renego_tx.clone());
+                            session_id = Some(sid);
+                        }
+                    }
+                    SignalMessage::Ice { candidate, sdp_mid, sdp_mline_index } => {
+                        let init = RTCIceCandidateInit { candidate, sdp_mid, sdp_mline_index, ..Default::default() };
+                        if let Err(e) = peer_connection.add_ice_candidate(init).await {
+                            gst::warning!(CAT, "Failed to add remote ICE candidate: {}", e);
+                        }
+                    }
+                    SignalMessage::Bye => break,
+                    SignalMessage::Answer { sdp } => {
+                        // Reply to a server-initiated renegotiation offer.
+                        match RTCSessionDescription::answer(sdp) {
+                            Ok(answer) => {
+                                if let Err(e) = peer_connection.set_remote_description(answer).await {
+                                    gst::warning!(CAT, "Failed to apply renegotiation answer: {}", e);
+                                }
+                            }
+                            Err(e) => gst::warning!(CAT, "Malformed renegotiation answer: {}", e),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(session_id) = session_id {
+        let mut guard = state.lock().unwrap();
+        guard.peer_connections.remove(&session_id);
+        guard.peer_bitrates.remove(&session_id);
+        guard.renegotiation_txs.remove(&session_id);
+        if let Some(tx) = &guard.unblock_tx {
+            let _ = tx.try_send(guard.peer_connections.len() as i32);
+        }
+    }
+    let _ = peer_connection.close().await;
+    gst::info!(CAT, "🔌 WebSocket signalling connection closed");
+}
+
+async fn on_offer(
+    socket: &mut WebSocket,
+    peer_connection: &Arc<webrtc::peer_connection::RTCPeerConnection>,
+    sdp: String,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    peer_connection.set_remote_description(RTCSessionDescription::offer(sdp)?).await?;
+    let answer = peer_connection.create_answer(None).await?;
+    peer_connection.set_local_description(answer.clone()).await?;
+    // Send the answer immediately without waiting for ICE gathering; candidates
+    // trickle separately.
+    let json = serde_json::to_string(&SignalMessage::Answer { sdp: answer.sdp })?;
+    socket.send(Message::Text(json.into())).await?;
+    Ok(())
+}